@@ -1,16 +1,21 @@
 use eframe::egui;
 use egui::{Color32, RichText, Vec2};
-use enigo::{Enigo, MouseButton, MouseControllable};
-use rand::Rng;
-use std::sync::{Arc, Mutex};
+use enigo::{Enigo, KeyboardControllable, MouseButton, MouseControllable};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use serde::{Deserialize, Serialize};
 use once_cell::sync::Lazy;
 use hotkey::Listener;
+use std::collections::{HashSet, VecDeque};
 use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
 use std::path::PathBuf;
-// use rfd::FileDialog;
+use rfd::FileDialog;
 use ctrlc;
 
 // Click Mode Enum
@@ -18,8 +23,10 @@ use ctrlc;
 enum ClickMode {
     FixedInterval,   // Fixed Interval Click
     RandomInterval,  // Random Interval Click
-    Continuous,      // Continuous Click (as fast as possible)
+    Continuous,      // Continuous Click (closed-loop towards a target CPS)
     Pattern,         // Pattern Click (custom sequence)
+    Ramp,            // Ramp Click (interval eases from a start value to a target)
+    Burst,           // Burst Click (rapid bursts separated by pauses)
 }
 
 impl ClickMode {
@@ -29,11 +36,33 @@ impl ClickMode {
             ClickMode::RandomInterval => "Random Interval",
             ClickMode::Continuous => "Continuous",
             ClickMode::Pattern => "Pattern",
+            ClickMode::Ramp => "Ramp",
+            ClickMode::Burst => "Burst",
+        }
+    }
+    
+    // 编辑窗口中的悬停说明
+    fn description(&self) -> &'static str {
+        match self {
+            ClickMode::FixedInterval => "每次点击之间等待固定的时间，可以用毫秒间隔或每秒次数（CPS）表示",
+            ClickMode::RandomInterval => "每次点击前在最小和最大间隔（毫秒）之间随机取一个等待时间",
+            ClickMode::Continuous => "尽量以目标CPS连续点击，每秒根据实际速率自动修正间隔",
+            ClickMode::Pattern => "按逗号分隔的间隔序列（毫秒）依次等待，例如 100,200,300，用完后从头循环",
+            ClickMode::Ramp => "间隔在设定时长内从起始值线性变为目标值，之后保持目标值，模拟逐渐加快",
+            ClickMode::Burst => "以短间隔连续点击一组，然后停顿一段时间再点下一组，适合有冷却时间的场景",
         }
     }
     
     fn all() -> Vec<ClickMode> {
-        vec![ClickMode::FixedInterval, ClickMode::RandomInterval, ClickMode::Continuous, ClickMode::Pattern]
+        vec![ClickMode::FixedInterval, ClickMode::RandomInterval, ClickMode::Continuous, ClickMode::Pattern, ClickMode::Ramp, ClickMode::Burst]
+    }
+    
+    // 编辑窗口可选的模式，连续模式需要在设置中允许
+    fn available(allow_continuous: bool) -> Vec<ClickMode> {
+        Self::all()
+            .into_iter()
+            .filter(|mode| allow_continuous || *mode != ClickMode::Continuous)
+            .collect()
     }
 }
 
@@ -67,30 +96,658 @@ impl MouseButtonType {
     }
 }
 
+// Action Type Enum
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum ActionType {
+    MouseClick,                                  // 普通点击
+    Drag { from: (i32, i32), to: (i32, i32) },   // 在起点按下，移动到终点后松开
+    FollowPath,                                  // 沿录制的鼠标路径移动，在录到的位置点击
+    ButtonCombo,                                 // 同时按下多个鼠标按键，再一起松开
+    PointSequence,                               // 依次移到配置的各个坐标点击，循环使用
+    KeyPress,                                    // 按下并松开配置的键盘按键，不点击鼠标
+}
+
+impl ActionType {
+    fn name(&self) -> &'static str {
+        match self {
+            ActionType::MouseClick => "Click",
+            ActionType::Drag { .. } => "Drag",
+            ActionType::FollowPath => "Path",
+            ActionType::ButtonCombo => "Combo",
+            ActionType::PointSequence => "Points",
+            ActionType::KeyPress => "Key",
+        }
+    }
+    
+    fn description(&self) -> &'static str {
+        match self {
+            ActionType::MouseClick => "在鼠标当前位置（或设置的固定坐标）点击",
+            ActionType::Drag { .. } => "在起点按下鼠标，平滑移动到终点后松开",
+            ActionType::FollowPath => "按录制时的速度沿鼠标路径移动，在录制时点击过的位置点击",
+            ActionType::ButtonCombo => "在鼠标当前位置同时按下选中的多个按键，然后一起松开",
+            ActionType::PointSequence => "每次移到列表中的下一个坐标点击，到末尾后从头循环",
+            ActionType::KeyPress => "每次按下并松开选中的键盘按键，间隔模式和鼠标点击相同",
+        }
+    }
+    
+    fn all() -> Vec<ActionType> {
+        vec![ActionType::MouseClick, ActionType::Drag { from: (0, 0), to: (0, 0) }, ActionType::FollowPath, ActionType::ButtonCombo, ActionType::PointSequence, ActionType::KeyPress]
+    }
+    
+    // 该动作会用到的绝对屏幕坐标
+    fn target_points(&self) -> Vec<(i32, i32)> {
+        match self {
+            // 坐标序列保存在配置的click_points中
+            ActionType::MouseClick | ActionType::FollowPath | ActionType::ButtonCombo | ActionType::PointSequence | ActionType::KeyPress => Vec::new(),
+            ActionType::Drag { from, to } => vec![*from, *to],
+        }
+    }
+}
+
+// 固定坐标点击的随机偏移：两个均匀分布相加近似正态，集中在中心附近，并限制在半径radius的圆内
+fn jitter_offset<R: Rng>(rng: &mut R, radius: u32) -> (i32, i32) {
+    if radius == 0 {
+        return (0, 0);
+    }
+    let radius = radius as f64;
+    let mut sample = || (rng.gen_range(-1.0..=1.0) + rng.gen_range(-1.0..=1.0)) / 2.0 * radius;
+    let (dx, dy) = (sample(), sample());
+    let length = (dx * dx + dy * dy).sqrt();
+    let scale = if length > radius { radius / length } else { 1.0 };
+    // 向零取整，取整后也不会超出半径
+    ((dx * scale) as i32, (dy * scale) as i32)
+}
+
+// 双击/三击时连续点击之间的默认间隔，和系统双击判定时间相比足够短；超过判定时间会被当成多次单击
+const DEFAULT_MULTI_CLICK_GAP_MS: u64 = 50;
+const MAX_MULTI_CLICK_GAP_MS: u64 = 500;
+const MAX_CLICKS_PER_ACTION: u32 = 5;
+
+// 拖动时的插值步数和每步间隔
+const DRAG_STEPS: i32 = 20;
+const DRAG_STEP_DELAY_MS: u64 = 10;
+
+// Mouse Path
+// 录制时的一个采样点，t_ms为距录制开始的毫秒数
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+struct PathPoint {
+    x: i32,
+    y: i32,
+    t_ms: u64,
+    click: bool, // 录制时在这里按下了左键
+}
+
+// 录制采样间隔和回放时的插值步长
+const PATH_SAMPLE_INTERVAL: Duration = Duration::from_millis(20);
+const PATH_REPLAY_STEP_MS: u64 = 5;
+const DEFAULT_PATH_RECORD_SECS: u64 = 5;
+
+// Coordinate Capture Target
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum CaptureTarget {
+    DragFrom,
+    DragTo,
+    ClickPosition, // 普通点击的固定坐标
+    SequencePoint(usize), // 坐标序列中的第几个点
+    Pixel, // 同时记录像素条件的坐标和当前颜色
+}
+
+// Pixel Condition
+// 只有当屏幕上指定像素的颜色与期望颜色相近时才点击
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+struct PixelCondition {
+    x: i32,
+    y: i32,
+    color: [u8; 3], // 期望的RGB
+    tolerance: u8,  // 每个通道允许的最大偏差
+}
+
+impl Default for PixelCondition {
+    fn default() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            color: [0, 255, 0],
+            tolerance: 10,
+        }
+    }
+}
+
+impl PixelCondition {
+    fn matches(&self, color: [u8; 3]) -> bool {
+        self.color.iter()
+            .zip(color.iter())
+            .all(|(expected, actual)| expected.abs_diff(*actual) <= self.tolerance)
+    }
+}
+
+// 像素不匹配时重新采样的间隔，避免空转占满CPU
+const PIXEL_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+// 捕获坐标前的倒计时，留时间把鼠标移到目标位置
+const CAPTURE_DELAY: Duration = Duration::from_secs(3);
+
+// 解析其他工具复制的坐标，例如 "(1024, 768)"、"1024,768"、"X: 1024 Y: 768"
+fn parse_coordinate(text: &str) -> Option<(i32, i32)> {
+    let numbers = text
+        .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<i32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    
+    match numbers[..] {
+        [x, y] => Some((x, y)),
+        _ => None,
+    }
+}
+
+fn paste_coordinate() -> Result<(i32, i32), String> {
+    let text = arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|err| format!("无法读取剪贴板: {}", err))?;
+    parse_coordinate(&text).ok_or_else(|| format!("剪贴板内容不是坐标: {}", text.trim()))
+}
+
+// Pending Action
+// 已安排但尚未发生的定时动作，在"计划"面板中列出，可以取消
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum PendingAction {
+    Capture(CaptureTarget, Instant), // 到时读取鼠标位置
+    PathRecording(Instant),          // 录制开始时间，之后录制仍在进行
+    ResumeOnUnlock,                  // 解锁后自动恢复运行
+}
+
+impl PendingAction {
+    // 预定发生的时间，None表示取决于外部事件
+    fn due(&self) -> Option<Instant> {
+        match self {
+            PendingAction::Capture(_, at) | PendingAction::PathRecording(at) => Some(*at),
+            PendingAction::ResumeOnUnlock => None,
+        }
+    }
+    
+    fn description(&self) -> String {
+        let remaining = |at: &Instant| at.saturating_duration_since(Instant::now()).as_secs() + 1;
+        match self {
+            PendingAction::Capture(CaptureTarget::Pixel, at) => format!("{}秒后取色", remaining(at)),
+            PendingAction::Capture(_, at) => format!("{}秒后捕获坐标", remaining(at)),
+            PendingAction::PathRecording(at) if Instant::now() < *at => format!("{}秒后开始录制路径", remaining(at)),
+            PendingAction::PathRecording(_) => "正在录制路径".to_string(),
+            PendingAction::ResumeOnUnlock => "屏幕解锁后恢复运行".to_string(),
+        }
+    }
+}
+
+// Config Grid Column Enum
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+enum GridColumn {
+    Number,
+    Name,
+    ClickType,
+    Interval,
+    RunMode, // 自动停止条件
+    LastUsed,
+}
+
+impl GridColumn {
+    fn name(&self) -> &'static str {
+        match self {
+            GridColumn::Number => "No.",
+            GridColumn::Name => "Window Title",
+            GridColumn::ClickType => "Click Type",
+            GridColumn::Interval => "Interval",
+            GridColumn::RunMode => "Run",
+            GridColumn::LastUsed => "最近使用",
+        }
+    }
+    
+    fn all() -> Vec<GridColumn> {
+        vec![GridColumn::Number, GridColumn::Name, GridColumn::ClickType, GridColumn::Interval, GridColumn::RunMode, GridColumn::LastUsed]
+    }
+}
+
+// 配置图标可选的表情符号
+const CONFIG_ICONS: [&str; 10] = ["🖱", "⚡", "🎮", "🎯", "⏱", "🔁", "⭐", "🔥", "💤", "🛠"];
+
 // Configuration Structure for Save and Load
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
 struct ClickerConfig {
     name: String,
+    icon: String,                  // 显示在名称前的表情图标，为空表示无图标
     click_mode: ClickMode,
     mouse_button: MouseButtonType,
+    combo_buttons: Vec<MouseButtonType>, // 组合按键动作同时按下的按键，为空时使用mouse_button
+    action_type: ActionType,
     fixed_interval_ms: u64,
+    rate_cps: Option<f64>,         // 固定模式以频率表示，设置时覆盖fixed_interval_ms
     min_random_interval_ms: u64,
     max_random_interval_ms: u64,
     pattern_intervals: Vec<u64>,
+    randomize_start_index: bool,   // 模式点击每次从随机位置开始
+    ramp_start_ms: u64,            // 渐快模式开始时的间隔
+    ramp_end_ms: u64,              // 渐快模式最终保持的间隔
+    ramp_duration_ms: u64,         // 从起始间隔变到最终间隔所用的时间
+    burst_size: u32,               // 连发模式每组的点击数
+    burst_interval_ms: u64,        // 组内点击之间的间隔
+    burst_pause_ms: u64,           // 每组点完后的停顿
+    target_cps: u32,               // 连续模式的目标速率（次/秒）
+    relative_moves: Vec<(i32, i32)>, // 每次点击前相对移动鼠标，循环使用
+    cursor_offset: (i32, i32),     // 在鼠标当前位置加偏移处点击，点击后移回
+    target_position: Option<(i32, i32)>, // 普通点击在这个屏幕坐标点击后移回，None表示在鼠标当前位置点击
+    position_jitter_px: u32,       // 固定坐标点击时随机偏移的最大半径（像素），0表示不偏移
+    clicks_per_action: u32,        // 普通点击和坐标序列每次连续点击几下（2为双击），统计按单次点击计数
+    multi_click_gap_ms: u64,       // 连续点击之间的间隔
+    post_click_delay_ms: u64,      // 点击（含按住释放）后额外等待，让目标程序处理点击，不计入间隔
+    path: Vec<PathPoint>,          // 路径动作录制的鼠标轨迹
+    click_points: Vec<(i32, i32)>, // 坐标序列动作依次点击的屏幕坐标
+    press_key: String,             // 按键动作按下的键，名称见HOTKEY_KEYS
+    key_repeat: bool,              // 按住按键并模拟系统自动重复，不再按点击间隔按下松开
+    key_repeat_delay_ms: u64,      // 按下后到开始自动重复的时间
+    key_repeat_interval_ms: u64,   // 自动重复时两次按下事件的间隔
+    stuck_timeout_secs: u64,       // 画面这么久没有变化就停止，0表示不检查
+    target_window_title: String,   // 目标窗口标题，为空表示不限定窗口
+    stop_on_window_lost: bool,     // 目标窗口消失时自动停止
+    confirm_before_start: bool,    // 开始前弹出确认框
+    preposition_cursor: bool,      // 开始时先把鼠标移到动作的起点
+    restrict_to_window_client: bool, // 坐标落在目标窗口客户区外时跳过点击
+    max_cps: Option<f64>,          // 本配置的CPS上限，与全局上限取较小值
+    pixel_condition: Option<PixelCondition>, // 设置后只在像素颜色匹配时点击
+    max_clicks: Option<u64>,       // 点击这么多次后自动停止，None表示一直运行
+    max_duration_secs: Option<u64>, // 运行这么多秒后自动停止，和点击上限先到者生效
+    loop_count: u32,               // 达到点击或时长上限后整体重复几轮，1表示不重复
+    loop_gap_ms: u64,              // 两轮之间的停顿
+    last_used: Option<i64>,        // 最近一次使用的Unix时间戳（秒），None表示从未使用
 }
 
 impl Default for ClickerConfig {
     fn default() -> Self {
         Self {
             name: "默认配置".to_string(),
+            icon: String::new(),
             click_mode: ClickMode::FixedInterval,
             mouse_button: MouseButtonType::Left,
+            combo_buttons: Vec::new(),
+            action_type: ActionType::MouseClick,
             fixed_interval_ms: 100,
+            rate_cps: None,
             min_random_interval_ms: 50,
             max_random_interval_ms: 200,
             pattern_intervals: vec![100, 200, 300],
+            randomize_start_index: false,
+            ramp_start_ms: 500,
+            ramp_end_ms: 100,
+            ramp_duration_ms: 10_000,
+            burst_size: 5,
+            burst_interval_ms: 30,
+            burst_pause_ms: 1000,
+            target_cps: 100,
+            relative_moves: Vec::new(),
+            cursor_offset: (0, 0),
+            target_position: None,
+            position_jitter_px: 0,
+            clicks_per_action: 1,
+            multi_click_gap_ms: DEFAULT_MULTI_CLICK_GAP_MS,
+            post_click_delay_ms: 0,
+            path: Vec::new(),
+            click_points: Vec::new(),
+            press_key: "Space".to_string(),
+            key_repeat: false,
+            key_repeat_delay_ms: 500,
+            key_repeat_interval_ms: 33,
+            stuck_timeout_secs: 0,
+            target_window_title: String::new(),
+            stop_on_window_lost: false,
+            confirm_before_start: false,
+            preposition_cursor: false,
+            restrict_to_window_client: false,
+            max_cps: None,
+            pixel_condition: None,
+            max_clicks: None,
+            max_duration_secs: None,
+            loop_count: 1,
+            loop_gap_ms: 1000,
+            last_used: None,
+        }
+    }
+}
+
+impl ClickerConfig {
+    fn display_name(&self) -> String {
+        if self.icon.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} {}", self.icon, self.name)
+        }
+    }
+    
+    fn interval_text(&self) -> String {
+        match self.click_mode {
+            ClickMode::FixedInterval => match self.rate_cps {
+                Some(cps) => format_cps(cps),
+                None => format_interval_ms(self.fixed_interval_ms),
+            },
+            ClickMode::RandomInterval => format!("{}-{} ms", self.min_random_interval_ms, self.max_random_interval_ms),
+            ClickMode::Continuous => format_cps(self.target_cps as f64),
+            ClickMode::Pattern => {
+                let intervals = self.pattern_intervals.iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!("[{}] ms", intervals)
+            }
+            ClickMode::Ramp => format!(
+                "{}→{} ms ({:.1}s)",
+                self.ramp_start_ms, self.ramp_end_ms, self.ramp_duration_ms as f64 / 1000.0
+            ),
+            ClickMode::Burst => format!(
+                "{}×{} ms / {}",
+                self.burst_size, self.burst_interval_ms, format_interval_ms(self.burst_pause_ms)
+            ),
+        }
+    }
+    
+    // 表格中的运行方式标记：∞为一直运行，×N为点击上限，Ns为时长上限，↻N为重复轮数
+    fn run_mode_text(&self) -> String {
+        let mut text = match (self.max_clicks, self.max_duration_secs) {
+            (None, None) => "∞".to_string(),
+            (Some(clicks), None) => format!("×{}", clicks),
+            (None, Some(secs)) => format!("{}s", secs),
+            (Some(clicks), Some(secs)) => format!("×{} / {}s", clicks, secs),
+        };
+        if self.loop_count > 1 && (self.max_clicks.is_some() || self.max_duration_secs.is_some()) {
+            text.push_str(&format!(" ↻{}", self.loop_count));
+        }
+        text
+    }
+    
+    fn last_used_text(&self) -> String {
+        let Some(last_used) = self.last_used else {
+            return "从未".to_string();
+        };
+        
+        format_elapsed_ago((unix_timestamp() - last_used).max(0))
+    }
+    
+    // 用于排序的名义间隔（毫秒）
+    fn nominal_interval_ms(&self) -> f64 {
+        match self.click_mode {
+            ClickMode::FixedInterval => match self.rate_cps {
+                Some(cps) if cps > 0.0 => 1000.0 / cps,
+                _ => self.fixed_interval_ms as f64,
+            },
+            ClickMode::RandomInterval => (self.min_random_interval_ms + self.max_random_interval_ms) as f64 / 2.0,
+            ClickMode::Continuous => 1000.0 / self.target_cps.max(1) as f64,
+            ClickMode::Pattern => {
+                if self.pattern_intervals.is_empty() {
+                    100.0
+                } else {
+                    self.pattern_intervals.iter().sum::<u64>() as f64 / self.pattern_intervals.len() as f64
+                }
+            }
+            // 大部分时间以最终间隔运行
+            ClickMode::Ramp => self.ramp_end_ms.max(1) as f64,
+            // 一组加上停顿的总时长平均到每次点击
+            ClickMode::Burst => {
+                let size = self.burst_size.max(1) as u64;
+                ((size - 1) * self.burst_interval_ms + self.burst_pause_ms).max(1) as f64 / size as f64
+            }
+        }
+    }
+    
+    // 编辑窗口中切换模式：新模式的参数仍是默认值时按原模式的速率换算，用户改过的值保留
+    fn switch_mode(&mut self, mode: ClickMode) {
+        let interval_ms = self.nominal_interval_ms().max(1.0);
+        let defaults = ClickerConfig::default();
+        
+        match mode {
+            ClickMode::FixedInterval => {
+                if self.fixed_interval_ms == defaults.fixed_interval_ms && self.rate_cps.is_none() {
+                    self.fixed_interval_ms = (interval_ms.round() as u64).clamp(1, MAX_FIXED_INTERVAL_MS);
+                }
+            }
+            ClickMode::RandomInterval => {
+                if self.min_random_interval_ms == defaults.min_random_interval_ms && self.max_random_interval_ms == defaults.max_random_interval_ms {
+                    // 以原间隔为中心上下浮动一半，限制在滑块范围内
+                    self.min_random_interval_ms = ((interval_ms * 0.5).round() as u64).clamp(1, MAX_RANDOM_SLIDER_MS);
+                    self.max_random_interval_ms = ((interval_ms * 1.5).round() as u64).clamp(self.min_random_interval_ms, MAX_RANDOM_SLIDER_MS);
+                }
+            }
+            ClickMode::Continuous => {
+                if self.target_cps == defaults.target_cps {
+                    self.target_cps = ((1000.0 / interval_ms).round() as u32).clamp(1, 1000);
+                }
+            }
+            // 模式序列没有对应的单一速率，保留示例序列
+            ClickMode::Pattern => {}
+            ClickMode::Ramp => {
+                if self.ramp_start_ms == defaults.ramp_start_ms && self.ramp_end_ms == defaults.ramp_end_ms {
+                    // 以原速率为目标，从五倍间隔开始加快
+                    self.ramp_end_ms = (interval_ms.round() as u64).clamp(1, MAX_FIXED_INTERVAL_MS);
+                    self.ramp_start_ms = (self.ramp_end_ms * 5).min(MAX_FIXED_INTERVAL_MS);
+                }
+            }
+            // 连发的节奏和单一速率关系不大，保留默认参数
+            ClickMode::Burst => {}
+        }
+        
+        self.click_mode = mode;
+    }
+    
+    // 动作开始处的固定坐标，在鼠标当前位置点击时为None
+    fn start_point(&self) -> Option<(i32, i32)> {
+        match self.action_type {
+            ActionType::MouseClick => self.target_position,
+            ActionType::ButtonCombo | ActionType::KeyPress => None,
+            ActionType::PointSequence => self.click_points.first().copied(),
+            ActionType::Drag { from, .. } => Some(from),
+            ActionType::FollowPath => self.path.first().map(|point| (point.x, point.y)),
+        }
+    }
+    
+    // 启动前的检查：有错误时禁止启动，警告只提示
+    fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        
+        match self.click_mode {
+            ClickMode::RandomInterval if self.min_random_interval_ms > self.max_random_interval_ms => {
+                issues.push(ValidationIssue::Error(format!(
+                    "随机间隔的最小值 {} ms 大于最大值 {} ms",
+                    self.min_random_interval_ms, self.max_random_interval_ms
+                )));
+            }
+            ClickMode::Pattern if self.pattern_intervals.is_empty() => {
+                issues.push(ValidationIssue::Error("模式点击的间隔序列为空".to_string()));
+            }
+            ClickMode::Pattern if self.pattern_intervals.contains(&0) => {
+                issues.push(ValidationIssue::Warning("间隔序列中包含 0 ms".to_string()));
+            }
+            _ => {}
+        }
+        
+        if self.action_type == ActionType::FollowPath && self.path.is_empty() {
+            issues.push(ValidationIssue::Error("路径动作还没有录制路径".to_string()));
+        }
+        
+        if self.action_type == ActionType::PointSequence && self.click_points.is_empty() {
+            issues.push(ValidationIssue::Error("坐标序列还没有添加坐标".to_string()));
+        }
+        
+        if self.loop_count > 1 && self.max_clicks.is_none() && self.max_duration_secs.is_none() {
+            issues.push(ValidationIssue::Warning("设置了重复轮数但没有点击或时长上限，不会重复".to_string()));
+        }
+        
+        if self.action_type == ActionType::KeyPress && enigo_key(&self.press_key).is_none() {
+            let message = if windows_virtual_key(&self.press_key).is_some() {
+                format!("按键 {} 只在Windows上可用", self.press_key)
+            } else {
+                format!("不支持的按键: {}", self.press_key)
+            };
+            issues.push(ValidationIssue::Error(message));
+        }
+        
+        let mut points = self.action_type.target_points();
+        points.extend(self.target_position.filter(|_| self.action_type == ActionType::MouseClick));
+        points.extend(self.click_points.iter().copied().filter(|_| self.action_type == ActionType::PointSequence));
+        points.extend(self.pixel_condition.map(|condition| (condition.x, condition.y)));
+        points.extend(self.path.iter().map(|point| (point.x, point.y)).filter(|_| self.action_type == ActionType::FollowPath));
+        if let Some((x, y)) = find_offscreen_point(&points) {
+            issues.push(ValidationIssue::Error(format!("坐标 ({}, {}) 不在任何显示器上", x, y)));
+        }
+        
+        let interval_ms = self.nominal_interval_ms();
+        if interval_ms < MIN_SAFE_INTERVAL_MS {
+            issues.push(ValidationIssue::Warning(format!(
+                "间隔 {:.1} ms 低于安全下限 {} ms，目标程序可能无法及时响应",
+                interval_ms, MIN_SAFE_INTERVAL_MS
+            )));
+        }
+        
+        if !self.target_window_title.is_empty() && target_window_exists(&self.target_window_title) == Some(false) {
+            issues.push(ValidationIssue::Warning(format!("找不到目标窗口: {}", self.target_window_title)));
+        }
+        
+        issues
+    }
+}
+
+// 低于这个名义间隔时给出警告
+const MIN_SAFE_INTERVAL_MS: f64 = 5.0;
+
+// Config Validation Issue
+#[derive(Debug, Clone, PartialEq)]
+enum ValidationIssue {
+    Error(String),   // 禁止启动
+    Warning(String), // 允许启动，但给出提示
+}
+
+impl ValidationIssue {
+    fn is_error(&self) -> bool {
+        matches!(self, ValidationIssue::Error(_))
+    }
+    
+    fn message(&self) -> &str {
+        match self {
+            ValidationIssue::Error(message) | ValidationIssue::Warning(message) => message,
+        }
+    }
+}
+
+// 全局CPS上限的默认值，无论哪种模式实际速率都不会超过上限
+const DEFAULT_MAX_CPS: f64 = 200.0;
+
+// 运行中在主窗口上滚动滚轮调整间隔的步长，按住Ctrl使用细调步长
+const SCROLL_INTERVAL_STEP_MS: u64 = 10;
+const SCROLL_INTERVAL_FINE_STEP_MS: u64 = 1;
+
+fn effective_max_cps(config_max_cps: Option<f64>, app_max_cps: f64) -> f64 {
+    let max_cps = match config_max_cps {
+        Some(config_max_cps) => config_max_cps.min(app_max_cps),
+        None => app_max_cps,
+    };
+    max_cps.max(0.1)
+}
+
+// 固定间隔的上限（1小时），用于保持会话在线之类的低频点击
+const MAX_FIXED_INTERVAL_MS: u64 = 3_600_000;
+
+// 编辑窗口中随机间隔滑块的上限
+const MAX_RANDOM_SLIDER_MS: u64 = 1000;
+
+// 长间隔显示为分秒，例如 "5m 0s"
+fn format_interval_ms(ms: u64) -> String {
+    let secs = ms / 1000;
+    if ms < 60_000 {
+        format!("{} ms", ms)
+    } else if secs < 3600 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m {}s", secs / 3600, secs % 3600 / 60, secs % 60)
+    }
+}
+
+// CPS显示的小数位数，从设置同步，所有CPS显示都通过format_cps
+const DEFAULT_CPS_DECIMALS: usize = 1;
+static CPS_DECIMALS: AtomicUsize = AtomicUsize::new(DEFAULT_CPS_DECIMALS);
+
+fn format_cps(cps: f64) -> String {
+    format!("{:.*} CPS", CPS_DECIMALS.load(Ordering::Relaxed), cps)
+}
+
+fn format_elapsed_ago(elapsed_secs: i64) -> String {
+    match elapsed_secs {
+        i64::MIN..=59 => "刚刚".to_string(),
+        60..=3599 => format!("{}分钟前", elapsed_secs / 60),
+        3600..=86399 => format!("{}小时前", elapsed_secs / 3600),
+        _ => format!("{}天前", elapsed_secs / 86400),
+    }
+}
+
+fn unix_timestamp() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+// Stop Reason
+// 每次运行结束的原因，显示在状态栏并写入日志
+#[derive(Debug, Clone, PartialEq)]
+enum StopReason {
+    Manual,                  // 界面上的停止按钮
+    Hotkey,
+    Remote,                  // 命令端口
+    Exit,
+    ClickLimit(u64),
+    TimeLimit(u64),
+    WindowLost(String),
+    Offscreen(i32, i32),
+    PixelUnavailable,
+    Stuck(u64),
+    SessionLocked,
+    Panic,
+}
+
+impl StopReason {
+    fn description(&self) -> String {
+        match self {
+            StopReason::Manual => "手动停止".to_string(),
+            StopReason::Hotkey => "热键停止".to_string(),
+            StopReason::Remote => "命令端口停止".to_string(),
+            StopReason::Exit => "程序退出".to_string(),
+            StopReason::ClickLimit(limit) => format!("达到点击上限 ({} 次)", limit),
+            StopReason::TimeLimit(secs) => format!("达到运行时长上限 ({})", format_duration_secs(*secs)),
+            StopReason::WindowLost(title) => format!("目标窗口已关闭: {}", title),
+            StopReason::Offscreen(x, y) => format!("坐标 ({}, {}) 已不在任何显示器上", x, y),
+            StopReason::PixelUnavailable => "无法读取屏幕像素，像素条件不可用".to_string(),
+            StopReason::Stuck(secs) => format!("目标画面 {} 秒没有变化，可能已卡死", secs),
+            StopReason::SessionLocked => "屏幕已锁定或屏保已启动".to_string(),
+            StopReason::Panic => "点击线程异常退出".to_string(),
         }
     }
+    
+    fn status_text(&self) -> String {
+        format!("已停止: {}", self.description())
+    }
+}
+
+// 渐快模式在运行elapsed后的间隔（毫秒），到达ramp_duration_ms后保持ramp_end_ms
+fn ramp_interval_ms(start_ms: u64, end_ms: u64, duration_ms: u64, elapsed: Duration) -> f64 {
+    let progress = if duration_ms == 0 {
+        1.0
+    } else {
+        (elapsed.as_secs_f64() * 1000.0 / duration_ms as f64).min(1.0)
+    };
+    start_ms as f64 + (end_ms as f64 - start_ms as f64) * progress
+}
+
+// 随机间隔的上下界，手工改过的配置可能最小值大于最大值，这时互换
+fn random_interval_bounds(min_ms: u64, max_ms: u64) -> (u64, u64) {
+    (min_ms.min(max_ms), min_ms.max(max_ms))
+}
+
+fn format_duration_secs(secs: u64) -> String {
+    format!("{}分{}秒", secs / 60, secs % 60)
 }
 
 // Clicker Status
@@ -98,45 +755,257 @@ struct ClickerState {
     is_running: bool,
     click_mode: ClickMode,
     mouse_button: MouseButtonType,
+    combo_buttons: Vec<MouseButtonType>,
+    action_type: ActionType,
     fixed_interval_ms: u64,
+    rate_cps: Option<f64>,
     min_random_interval_ms: u64,
     max_random_interval_ms: u64,
     pattern_intervals: Vec<u64>,
+    randomize_start_index: bool,
+    ramp_start_ms: u64,
+    ramp_end_ms: u64,
+    ramp_duration_ms: u64,
+    burst_size: u32,
+    burst_interval_ms: u64,
+    burst_pause_ms: u64,
+    target_cps: u32,
+    relative_moves: Vec<(i32, i32)>,
+    cursor_offset: (i32, i32),
+    target_position: Option<(i32, i32)>,
+    position_jitter_px: u32,
+    clicks_per_action: u32,
+    multi_click_gap_ms: u64,
+    post_click_delay_ms: u64,
+    path: Vec<PathPoint>,
+    click_points: Vec<(i32, i32)>,
+    press_key: String,
+    key_repeat: bool,
+    key_repeat_delay_ms: u64,
+    key_repeat_interval_ms: u64,
+    stuck_timeout_secs: u64,
+    target_window_title: String,
+    stop_on_window_lost: bool,
+    restrict_to_window_client: bool,
+    max_cps: Option<f64>,
+    pixel_condition: Option<PixelCondition>,
+    loop_count: u32,
+    loop_gap_ms: u64,
+    app_max_cps: f64,             // 全局CPS上限，启动时从设置同步
+    stop_on_session_lock: bool,   // 锁屏时停止，启动时从设置同步
+    high_precision_timing: bool,  // 间隔最后一段忙等，启动时从设置同步
+    rng_seed: Option<u64>,        // 固定随机种子，None表示每次运行都不同
+    rate_limited: bool,           // 最近一次点击的间隔被CPS上限拉长
     click_count: u64,
+    skipped_clicks: u64,          // 因坐标不在目标窗口内而跳过的次数
+    run_start_count: u64,         // 本次运行开始时的点击数（累计计数时不为0）
+    loops_done: u32,              // 本次运行已完成的轮数
+    loop_start_count: u64,        // 本轮开始时的点击数，点击上限按轮计算
+    loop_start_elapsed: Duration, // 本轮开始时的运行时间，时长上限按轮计算
+    click_limit: Option<u64>,     // 本次运行的点击上限，达到后自动停止
+    time_limit: Option<Duration>, // 本次运行的时长上限，达到后自动停止
+    run_completed: bool,          // 本次运行因达到上限而自行结束（区别于手动停止）
     start_time: Option<Instant>,
     last_click_time: Option<Instant>,
+    recent_clicks: VecDeque<Instant>, // 最近RATE_WINDOW内每次点击的时间，用于计算当前速率
+    paused_at: Option<Instant>,   // 暂停的时刻，暂停期间点击线程保留计数和序列位置
+    held_key: Option<enigo::Key>, // 自动重复时按住未松开的按键，强制退出时也要松开
+    stop_reason: Option<StopReason>, // 点击线程自行停止的原因，留给界面显示
 }
 
 impl Default for ClickerState {
     fn default() -> Self {
-        Self {
-            is_running: false,
-            click_mode: ClickMode::FixedInterval,
-            mouse_button: MouseButtonType::Left,
-            fixed_interval_ms: 100,
-            min_random_interval_ms: 50,
-            max_random_interval_ms: 200,
-            pattern_intervals: vec![100, 200, 300],
-            click_count: 0,
-            start_time: None,
-            last_click_time: None,
+        Self::from(&ClickerConfig::default())
+    }
+}
+
+impl ClickerState {
+    // 本次运行会点击的绝对屏幕坐标
+    fn target_points(&self) -> Vec<(i32, i32)> {
+        let mut points = self.action_type.target_points();
+        match self.action_type {
+            ActionType::MouseClick => points.extend(self.target_position),
+            ActionType::PointSequence => points.extend(self.click_points.iter().copied()),
+            _ => {}
         }
+        points
+    }
+    
+    // 卡死检测监视的像素：优先用像素条件的坐标，否则用目标窗口客户区中心
+    fn stuck_watch_point(&self) -> Option<(i32, i32)> {
+        if let Some(condition) = self.pixel_condition {
+            return Some((condition.x, condition.y));
+        }
+        if self.target_window_title.is_empty() {
+            return None;
+        }
+        let (left, top, right, bottom) = target_client_rect(&self.target_window_title)?;
+        Some(((left + right) / 2, (top + bottom) / 2))
+    }
+    
+    fn stats(&self) -> ClickerStats {
+        ClickerStats {
+            is_running: self.is_running,
+            click_count: self.click_count,
+            skipped_clicks: self.skipped_clicks,
+            run_start_count: self.run_start_count,
+            loop_start_count: self.loop_start_count,
+            loop_start_elapsed: self.loop_start_elapsed,
+            loop_index: self.loops_done + 1,
+            loop_count: self.loop_count,
+            click_limit: self.click_limit,
+            time_limit: self.time_limit,
+            run_completed: self.run_completed,
+            start_time: self.start_time,
+            last_click_time: self.last_click_time,
+            paused_at: self.paused_at,
+            current_cps: self.recent_clicks.iter().filter(|time| time.elapsed() < RATE_WINDOW).count() as f64 / RATE_WINDOW.as_secs_f64(),
+            rate_limited: self.rate_limited,
+            stop_reason: None,
+        }
+    }
+}
+
+// 互斥锁中毒后置位，由界面在状态栏报告
+static STATE_POISONED: AtomicBool = AtomicBool::new(false);
+
+// 点击线程持锁时panic会使互斥锁中毒，这里恢复数据并停止运行，避免界面跟着崩溃
+fn lock_state(state: &Mutex<ClickerState>) -> MutexGuard<'_, ClickerState> {
+    state.lock().unwrap_or_else(|poisoned| {
+        STATE_POISONED.store(true, Ordering::Relaxed);
+        state.clear_poison();
+        let mut guard = poisoned.into_inner();
+        guard.is_running = false;
+        guard.stop_reason = Some(StopReason::Panic);
+        guard
+    })
+}
+
+// Clicker Statistics Snapshot
+// 点击线程通过通道发送给界面，界面不必每帧锁定ClickerState
+#[derive(Clone, Default)]
+struct ClickerStats {
+    is_running: bool,
+    click_count: u64,
+    skipped_clicks: u64,
+    run_start_count: u64,
+    loop_start_count: u64,
+    loop_start_elapsed: Duration,
+    loop_index: u32, // 当前是第几轮，从1开始
+    loop_count: u32,
+    click_limit: Option<u64>,
+    time_limit: Option<Duration>,
+    run_completed: bool,
+    start_time: Option<Instant>,
+    last_click_time: Option<Instant>,
+    paused_at: Option<Instant>,
+    current_cps: f64, // 最近一秒的实际速率
+    rate_limited: bool,
+    stop_reason: Option<StopReason>,
+}
+
+impl ClickerStats {
+    // 本次运行的点击数
+    fn run_clicks(&self) -> u64 {
+        self.click_count.saturating_sub(self.run_start_count)
+    }
+    
+    // 本轮的点击数，不重复时等于本次运行的点击数
+    fn loop_clicks(&self) -> u64 {
+        self.click_count.saturating_sub(self.loop_start_count)
+    }
+    
+    // 本次运行的时长，暂停期间停在暂停的时刻
+    fn elapsed(&self) -> Option<Duration> {
+        let start_time = self.start_time?;
+        Some(self.paused_at.unwrap_or_else(Instant::now).saturating_duration_since(start_time))
+    }
+}
+
+impl ClickerState {
+    // 距离时长上限还剩多久，没有上限时返回None
+    fn time_remaining(&self) -> Option<Duration> {
+        let (limit, start_time) = (self.time_limit?, self.start_time?);
+        Some(limit.saturating_sub(start_time.elapsed().saturating_sub(self.loop_start_elapsed)))
     }
 }
 
+// 保留的状态消息条数
+const STATUS_HISTORY_LEN: usize = 20;
+
+// 计算当前速率的时间窗口
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+// 点击线程发送统计快照的最小间隔
+const STATS_SEND_INTERVAL: Duration = Duration::from_millis(30);
+
+// 每次点击后按钮高亮渐隐的时长
+const CLICK_FLASH_DURATION: Duration = Duration::from_millis(150);
+
 impl From<&ClickerConfig> for ClickerState {
     fn from(config: &ClickerConfig) -> Self {
+        let (min_random_interval_ms, max_random_interval_ms) =
+            random_interval_bounds(config.min_random_interval_ms, config.max_random_interval_ms);
         Self {
             is_running: false,
             click_mode: config.click_mode,
             mouse_button: config.mouse_button,
+            combo_buttons: config.combo_buttons.clone(),
+            action_type: config.action_type,
             fixed_interval_ms: config.fixed_interval_ms,
-            min_random_interval_ms: config.min_random_interval_ms,
-            max_random_interval_ms: config.max_random_interval_ms,
+            rate_cps: config.rate_cps,
+            min_random_interval_ms,
+            max_random_interval_ms,
             pattern_intervals: config.pattern_intervals.clone(),
+            randomize_start_index: config.randomize_start_index,
+            ramp_start_ms: config.ramp_start_ms,
+            ramp_end_ms: config.ramp_end_ms,
+            ramp_duration_ms: config.ramp_duration_ms,
+            burst_size: config.burst_size,
+            burst_interval_ms: config.burst_interval_ms,
+            burst_pause_ms: config.burst_pause_ms,
+            target_cps: config.target_cps,
+            relative_moves: config.relative_moves.clone(),
+            cursor_offset: config.cursor_offset,
+            target_position: config.target_position,
+            position_jitter_px: config.position_jitter_px,
+            clicks_per_action: config.clicks_per_action,
+            multi_click_gap_ms: config.multi_click_gap_ms,
+            post_click_delay_ms: config.post_click_delay_ms,
+            path: config.path.clone(),
+            click_points: config.click_points.clone(),
+            press_key: config.press_key.clone(),
+            key_repeat: config.key_repeat,
+            key_repeat_delay_ms: config.key_repeat_delay_ms,
+            key_repeat_interval_ms: config.key_repeat_interval_ms,
+            stuck_timeout_secs: config.stuck_timeout_secs,
+            target_window_title: config.target_window_title.clone(),
+            stop_on_window_lost: config.stop_on_window_lost,
+            restrict_to_window_client: config.restrict_to_window_client,
+            max_cps: config.max_cps,
+            pixel_condition: config.pixel_condition,
+            loop_count: config.loop_count,
+            loop_gap_ms: config.loop_gap_ms,
+            app_max_cps: DEFAULT_MAX_CPS,
+            stop_on_session_lock: false,
+            high_precision_timing: false,
+            rng_seed: None,
+            rate_limited: false,
             click_count: 0,
+            skipped_clicks: 0,
+            run_start_count: 0,
+            loops_done: 0,
+            loop_start_count: 0,
+            loop_start_elapsed: Duration::ZERO,
+            click_limit: None,
+            time_limit: None,
+            run_completed: false,
             start_time: None,
             last_click_time: None,
+            recent_clicks: VecDeque::new(),
+            paused_at: None,
+            held_key: None,
+            stop_reason: None,
         }
     }
 }
@@ -147,57 +1016,2604 @@ static HOTKEY_ACTIVE: Lazy<Arc<Mutex<bool>>> = Lazy::new(|| Arc::new(Mutex::new(
 // Hotkey Command Channel
 static HOTKEY_COMMAND: Lazy<Arc<Mutex<Option<bool>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
-// Config File Path
-fn get_config_dir() -> PathBuf {
-    let path = if let Some(proj_dirs) = directories::ProjectDirs::from("com", "SeriousClick", "SeriousClick") {
-        proj_dirs.config_dir().to_path_buf()
-    } else {
-        PathBuf::from(".").join(".config")
-    };
-    
-    if !path.exists() {
-        let _ = std::fs::create_dir_all(&path);
+// Hotkey Diagnostics
+// 最近一次收到热键命令的时间
+static HOTKEY_LAST_COMMAND: Lazy<Arc<Mutex<Option<Instant>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
+
+// 监听线程的注册结果，None表示尚未注册
+static HOTKEY_REGISTRATION: Lazy<Arc<Mutex<Option<Result<(), String>>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
+
+// 默认的开始/停止热键: F8，无修饰键（Windows虚拟键码），可在热键菜单中重新绑定
+const DEFAULT_HOTKEY_MODIFIERS: u32 = 0;
+const DEFAULT_HOTKEY_KEYCODE: u32 = 0x77;
+
+// 强制退出热键: Ctrl+Alt+F8，必须带修饰键，避免误触
+const EMERGENCY_EXIT_MODIFIERS: u32 = hotkey::modifiers::CONTROL | hotkey::modifiers::ALT;
+const EMERGENCY_EXIT_KEYCODE: u32 = DEFAULT_HOTKEY_KEYCODE;
+
+// 每次重新绑定热键加一，旧监听线程的回调发现代数不同后不再响应
+static HOTKEY_GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+// 可以绑定的按键: egui按键、Windows虚拟键码、显示名称
+const HOTKEY_KEYS: [(egui::Key, u32, &str); 62] = [
+    (egui::Key::F1, 0x70, "F1"),
+    (egui::Key::F2, 0x71, "F2"),
+    (egui::Key::F3, 0x72, "F3"),
+    (egui::Key::F4, 0x73, "F4"),
+    (egui::Key::F5, 0x74, "F5"),
+    (egui::Key::F6, 0x75, "F6"),
+    (egui::Key::F7, 0x76, "F7"),
+    (egui::Key::F8, 0x77, "F8"),
+    (egui::Key::F9, 0x78, "F9"),
+    (egui::Key::F10, 0x79, "F10"),
+    (egui::Key::F11, 0x7A, "F11"),
+    (egui::Key::F12, 0x7B, "F12"),
+    (egui::Key::A, 0x41, "A"),
+    (egui::Key::B, 0x42, "B"),
+    (egui::Key::C, 0x43, "C"),
+    (egui::Key::D, 0x44, "D"),
+    (egui::Key::E, 0x45, "E"),
+    (egui::Key::F, 0x46, "F"),
+    (egui::Key::G, 0x47, "G"),
+    (egui::Key::H, 0x48, "H"),
+    (egui::Key::I, 0x49, "I"),
+    (egui::Key::J, 0x4A, "J"),
+    (egui::Key::K, 0x4B, "K"),
+    (egui::Key::L, 0x4C, "L"),
+    (egui::Key::M, 0x4D, "M"),
+    (egui::Key::N, 0x4E, "N"),
+    (egui::Key::O, 0x4F, "O"),
+    (egui::Key::P, 0x50, "P"),
+    (egui::Key::Q, 0x51, "Q"),
+    (egui::Key::R, 0x52, "R"),
+    (egui::Key::S, 0x53, "S"),
+    (egui::Key::T, 0x54, "T"),
+    (egui::Key::U, 0x55, "U"),
+    (egui::Key::V, 0x56, "V"),
+    (egui::Key::W, 0x57, "W"),
+    (egui::Key::X, 0x58, "X"),
+    (egui::Key::Y, 0x59, "Y"),
+    (egui::Key::Z, 0x5A, "Z"),
+    (egui::Key::Num0, 0x30, "0"),
+    (egui::Key::Num1, 0x31, "1"),
+    (egui::Key::Num2, 0x32, "2"),
+    (egui::Key::Num3, 0x33, "3"),
+    (egui::Key::Num4, 0x34, "4"),
+    (egui::Key::Num5, 0x35, "5"),
+    (egui::Key::Num6, 0x36, "6"),
+    (egui::Key::Num7, 0x37, "7"),
+    (egui::Key::Num8, 0x38, "8"),
+    (egui::Key::Num9, 0x39, "9"),
+    (egui::Key::Space, 0x20, "Space"),
+    (egui::Key::PageUp, 0x21, "PageUp"),
+    (egui::Key::PageDown, 0x22, "PageDown"),
+    (egui::Key::End, 0x23, "End"),
+    (egui::Key::Home, 0x24, "Home"),
+    (egui::Key::ArrowLeft, 0x25, "Left"),
+    (egui::Key::ArrowUp, 0x26, "Up"),
+    (egui::Key::ArrowRight, 0x27, "Right"),
+    (egui::Key::ArrowDown, 0x28, "Down"),
+    (egui::Key::Insert, 0x2D, "Insert"),
+    (egui::Key::Delete, 0x2E, "Delete"),
+    (egui::Key::Enter, 0x0D, "Enter"),
+    (egui::Key::Tab, 0x09, "Tab"),
+    (egui::Key::Backspace, 0x08, "Backspace"),
+];
+
+fn hotkey_name(modifiers: u32, keycode: u32) -> String {
+    let mut name = String::new();
+    for (modifier, label) in [(hotkey::modifiers::CONTROL, "Ctrl+"), (hotkey::modifiers::ALT, "Alt+"), (hotkey::modifiers::SHIFT, "Shift+")] {
+        if modifiers & modifier != 0 {
+            name.push_str(label);
+        }
     }
-    
-    path.join("configs.json")
+    match HOTKEY_KEYS.iter().find(|(_, code, _)| *code == keycode) {
+        Some((_, _, key_name)) => name.push_str(key_name),
+        None => name.push_str(&format!("0x{:02X}", keycode)),
+    }
+    name
 }
 
-// Application State
-struct SeriousClickerApp {
-    state: Arc<Mutex<ClickerState>>,
-    clicker_thread: Option<thread::JoinHandle<()>>,
-    configs: Vec<ClickerConfig>,
-    selected_config_index: usize,
-    editing_config: ClickerConfig,
-    is_editing: bool,
-    pattern_input: String,
-    status_message: String,
+// 把界面中按下的键转换为 (修饰键, 虚拟键码)，不支持的键返回None
+fn hotkey_from_egui(key: egui::Key, modifiers: egui::Modifiers) -> Option<(u32, u32)> {
+    let (_, keycode, _) = HOTKEY_KEYS.iter().find(|(egui_key, _, _)| *egui_key == key)?;
+    let mut hotkey_modifiers = 0;
+    if modifiers.ctrl {
+        hotkey_modifiers |= hotkey::modifiers::CONTROL;
+    }
+    if modifiers.alt {
+        hotkey_modifiers |= hotkey::modifiers::ALT;
+    }
+    if modifiers.shift {
+        hotkey_modifiers |= hotkey::modifiers::SHIFT;
+    }
+    Some((hotkey_modifiers, *keycode))
+}
+
+// 按键动作下拉框中的按键，按分组显示；字母和数字通过捕获选择
+const PRESS_KEY_GROUPS: [(&str, &[&str]); 5] = [
+    ("功能键", &[
+        "F1", "F2", "F3", "F4", "F5", "F6", "F7", "F8", "F9", "F10", "F11", "F12",
+        "F13", "F14", "F15", "F16", "F17", "F18", "F19", "F20", "F21", "F22", "F23", "F24",
+    ]),
+    ("方向和编辑", &[
+        "Up", "Down", "Left", "Right", "Home", "End", "PageUp", "PageDown",
+        "Insert", "Delete", "Backspace", "Enter", "Tab", "Space", "Escape",
+    ]),
+    ("修饰键", &["Shift", "Ctrl", "Alt"]),
+    ("小键盘", &[
+        "Numpad0", "Numpad1", "Numpad2", "Numpad3", "Numpad4", "Numpad5", "Numpad6", "Numpad7", "Numpad8", "Numpad9",
+        "Numpad+", "Numpad-", "Numpad*", "Numpad/", "Numpad.",
+    ]),
+    ("媒体", &["VolumeUp", "VolumeDown", "VolumeMute", "MediaPlayPause", "MediaNext", "MediaPrev", "MediaStop"]),
+];
+
+// 只能用Windows虚拟键码发送的按键，其他平台上键码含义不同，不可用
+fn windows_virtual_key(name: &str) -> Option<u16> {
+    if let Some(n) = name.strip_prefix('F').and_then(|n| n.parse::<u16>().ok()).filter(|n| (13..=24).contains(n)) {
+        return Some(0x7C + n - 13);
+    }
+    if let Some(n) = name.strip_prefix("Numpad").and_then(|n| n.parse::<u16>().ok()).filter(|n| *n <= 9) {
+        return Some(0x60 + n);
+    }
+    let code = match name {
+        "Insert" => 0x2D,
+        "Numpad*" => 0x6A,
+        "Numpad+" => 0x6B,
+        "Numpad-" => 0x6D,
+        "Numpad." => 0x6E,
+        "Numpad/" => 0x6F,
+        "VolumeMute" => 0xAD,
+        "VolumeDown" => 0xAE,
+        "VolumeUp" => 0xAF,
+        "MediaNext" => 0xB0,
+        "MediaPrev" => 0xB1,
+        "MediaStop" => 0xB2,
+        "MediaPlayPause" => 0xB3,
+        _ => return None,
+    };
+    Some(code)
+}
+
+// 按键动作的按键名称（与HOTKEY_KEYS和PRESS_KEY_GROUPS中的名称相同）转换为enigo按键
+fn enigo_key(name: &str) -> Option<enigo::Key> {
+    let key = match name {
+        "F1" => enigo::Key::F1,
+        "F2" => enigo::Key::F2,
+        "F3" => enigo::Key::F3,
+        "F4" => enigo::Key::F4,
+        "F5" => enigo::Key::F5,
+        "F6" => enigo::Key::F6,
+        "F7" => enigo::Key::F7,
+        "F8" => enigo::Key::F8,
+        "F9" => enigo::Key::F9,
+        "F10" => enigo::Key::F10,
+        "F11" => enigo::Key::F11,
+        "F12" => enigo::Key::F12,
+        "Space" => enigo::Key::Space,
+        "PageUp" => enigo::Key::PageUp,
+        "PageDown" => enigo::Key::PageDown,
+        "End" => enigo::Key::End,
+        "Home" => enigo::Key::Home,
+        "Left" => enigo::Key::LeftArrow,
+        "Up" => enigo::Key::UpArrow,
+        "Right" => enigo::Key::RightArrow,
+        "Down" => enigo::Key::DownArrow,
+        "Delete" => enigo::Key::Delete,
+        "Enter" => enigo::Key::Return,
+        "Tab" => enigo::Key::Tab,
+        "Backspace" => enigo::Key::Backspace,
+        "Escape" => enigo::Key::Escape,
+        "Shift" => enigo::Key::Shift,
+        "Ctrl" => enigo::Key::Control,
+        "Alt" => enigo::Key::Alt,
+        _ => {
+            if let Some(code) = windows_virtual_key(name) {
+                return cfg!(windows).then_some(enigo::Key::Raw(code));
+            }
+            // 字母和数字按当前键盘布局输入
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphanumeric() => enigo::Key::Layout(c.to_ascii_lowercase()),
+                _ => return None,
+            }
+        }
+    };
+    Some(key)
+}
+
+// 监听线程的线程ID，重新绑定时向它发送WM_QUIT结束消息循环，释放旧的热键注册
+#[cfg(windows)]
+static HOTKEY_LISTENER_THREAD_ID: std::sync::atomic::AtomicU32 = std::sync::atomic::AtomicU32::new(0);
+
+#[cfg(windows)]
+fn quit_hotkey_listener() {
+    let thread_id = HOTKEY_LISTENER_THREAD_ID.swap(0, Ordering::SeqCst);
+    if thread_id != 0 {
+        unsafe {
+            win32::PostThreadMessageW(thread_id, win32::WM_QUIT, 0, 0);
+        }
+    }
+}
+
+// 其他平台无法中断监听线程，只靠HOTKEY_GENERATION让旧回调失效
+#[cfg(not(windows))]
+fn quit_hotkey_listener() {}
+
+// 设置中是否启用强制退出，热键回调中读取
+static EMERGENCY_EXIT_ENABLED: AtomicBool = AtomicBool::new(false);
+
+// 强制退出热键注册失败的原因，由界面线程取出后显示
+static EMERGENCY_EXIT_REGISTRATION: Lazy<Arc<Mutex<Option<String>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
+
+// 最后手段：停止点击、松开所有鼠标按键和按住的键盘按键、关闭热键后直接结束进程
+fn emergency_exit(state: &Arc<Mutex<ClickerState>>) -> ! {
+    let held_key = {
+        let mut state = lock_state(state);
+        state.is_running = false;
+        state.held_key.take()
+    };
+    let mut enigo = Enigo::new();
+    for button in MouseButtonType::all() {
+        MouseControllable::mouse_up(&mut enigo, button.to_enigo_button());
+    }
+    if let Some(key) = held_key {
+        KeyboardControllable::key_up(&mut enigo, key);
+    }
+    *HOTKEY_ACTIVE.lock().unwrap() = false;
+    std::process::exit(0);
+}
+
+// Win32 窗口查询
+#[cfg(windows)]
+mod win32 {
+    use std::ffi::c_void;
+
+    pub type Hwnd = *mut c_void;
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct Rect {
+        pub left: i32,
+        pub top: i32,
+        pub right: i32,
+        pub bottom: i32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    pub const SM_XVIRTUALSCREEN: i32 = 76;
+    pub const SM_YVIRTUALSCREEN: i32 = 77;
+    pub const SM_CXVIRTUALSCREEN: i32 = 78;
+    pub const SM_CYVIRTUALSCREEN: i32 = 79;
+
+    #[link(name = "user32")]
+    extern "system" {
+        pub fn FindWindowW(class_name: *const u16, window_name: *const u16) -> Hwnd;
+        pub fn GetSystemMetrics(index: i32) -> i32;
+        pub fn GetClientRect(hwnd: Hwnd, rect: *mut Rect) -> i32;
+        pub fn ClientToScreen(hwnd: Hwnd, point: *mut Point) -> i32;
+        pub fn MessageBeep(beep_type: u32) -> i32;
+        pub fn GetDC(hwnd: Hwnd) -> *mut c_void;
+        pub fn GetAsyncKeyState(key: i32) -> i16;
+        pub fn GetForegroundWindow() -> Hwnd;
+        pub fn ReleaseDC(hwnd: Hwnd, hdc: *mut c_void) -> i32;
+        pub fn OpenInputDesktop(flags: u32, inherit: i32, desired_access: u32) -> *mut c_void;
+        pub fn SwitchDesktop(desktop: *mut c_void) -> i32;
+        pub fn CloseDesktop(desktop: *mut c_void) -> i32;
+        pub fn PostThreadMessageW(thread_id: u32, msg: u32, wparam: usize, lparam: isize) -> i32;
+    }
+    
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn GetCurrentThreadId() -> u32;
+    }
+
+    pub const CLR_INVALID: u32 = 0xFFFF_FFFF;
+    pub const VK_LBUTTON: i32 = 0x01;
+    pub const DESKTOP_SWITCHDESKTOP: u32 = 0x0100;
+    pub const WM_QUIT: u32 = 0x0012;
+
+    #[link(name = "gdi32")]
+    extern "system" {
+        pub fn GetPixel(hdc: *mut c_void, x: i32, y: i32) -> u32;
+    }
+
+    #[link(name = "winmm")]
+    extern "system" {
+        pub fn timeBeginPeriod(period: u32) -> u32;
+        pub fn timeEndPeriod(period: u32) -> u32;
+    }
+    
+    // 转换为以0结尾的UTF-16字符串
+    pub fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+}
+
+// Target Window Lookup
+// 返回None表示当前平台不支持窗口查询
+#[cfg(windows)]
+fn find_target_window(title: &str) -> Option<win32::Hwnd> {
+    let title = win32::wide(title);
+    let hwnd = unsafe { win32::FindWindowW(std::ptr::null(), title.as_ptr()) };
+    if hwnd.is_null() { None } else { Some(hwnd) }
+}
+
+#[cfg(windows)]
+fn target_window_exists(title: &str) -> Option<bool> {
+    Some(find_target_window(title).is_some())
+}
+
+#[cfg(not(windows))]
+fn target_window_exists(_title: &str) -> Option<bool> {
+    None
+}
+
+// Target Window Focus
+// 状态栏显示目标窗口是否在前台
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum WindowFocus {
+    Focused,
+    Unfocused,
+    NotFound,
+}
+
+impl WindowFocus {
+    fn name(&self) -> &'static str {
+        match self {
+            WindowFocus::Focused => "已聚焦",
+            WindowFocus::Unfocused => "未聚焦",
+            WindowFocus::NotFound => "未找到",
+        }
+    }
+}
+
+#[cfg(windows)]
+fn target_window_focus(title: &str) -> Option<WindowFocus> {
+    let Some(hwnd) = find_target_window(title) else {
+        return Some(WindowFocus::NotFound);
+    };
+    let foreground = unsafe { win32::GetForegroundWindow() };
+    Some(if foreground == hwnd { WindowFocus::Focused } else { WindowFocus::Unfocused })
+}
+
+#[cfg(not(windows))]
+fn target_window_focus(_title: &str) -> Option<WindowFocus> {
+    None
+}
+
+// Screen Pixel Sampling
+// 返回None表示无法读取屏幕（平台不支持或没有权限）
+#[cfg(windows)]
+fn read_screen_pixel(x: i32, y: i32) -> Option<[u8; 3]> {
+    unsafe {
+        let hdc = win32::GetDC(std::ptr::null_mut());
+        if hdc.is_null() {
+            return None;
+        }
+        let color = win32::GetPixel(hdc, x, y);
+        win32::ReleaseDC(std::ptr::null_mut(), hdc);
+        if color == win32::CLR_INVALID {
+            return None;
+        }
+        // COLORREF为0x00BBGGRR
+        Some([(color & 0xFF) as u8, ((color >> 8) & 0xFF) as u8, ((color >> 16) & 0xFF) as u8])
+    }
+}
+
+#[cfg(not(windows))]
+fn read_screen_pixel(_x: i32, _y: i32) -> Option<[u8; 3]> {
+    None
+}
+
+// Mouse Button State
+// 返回None表示当前平台无法读取按键状态，录制路径时就不会记录点击
+#[cfg(windows)]
+fn left_button_down() -> Option<bool> {
+    let state = unsafe { win32::GetAsyncKeyState(win32::VK_LBUTTON) };
+    Some(state < 0) // 最高位表示当前按下
+}
+
+#[cfg(not(windows))]
+fn left_button_down() -> Option<bool> {
+    None
+}
+
+// Timer Resolution
+// Windows默认的定时器粒度约15.6ms，高精度计时期间临时改为1ms
+#[cfg(windows)]
+fn set_fine_timer_resolution(enabled: bool) {
+    unsafe {
+        if enabled {
+            win32::timeBeginPeriod(1);
+        } else {
+            win32::timeEndPeriod(1);
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn set_fine_timer_resolution(_enabled: bool) {}
+
+// Session Lock
+// 锁屏或屏保运行时输入桌面无法切换；返回None表示当前平台无法判断
+#[cfg(windows)]
+fn session_locked() -> Option<bool> {
+    unsafe {
+        let desktop = win32::OpenInputDesktop(0, 0, win32::DESKTOP_SWITCHDESKTOP);
+        if desktop.is_null() {
+            return Some(true);
+        }
+        let switched = win32::SwitchDesktop(desktop) != 0;
+        win32::CloseDesktop(desktop);
+        Some(!switched)
+    }
+}
+
+#[cfg(not(windows))]
+fn session_locked() -> Option<bool> {
+    None
+}
+
+// 锁屏期间等待解锁时的检查间隔
+const UNLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Target Window Client Area Guard
+// 所有坐标都在目标窗口客户区内时返回true；找不到窗口时返回false
+#[cfg(windows)]
+fn points_inside_target_window(title: &str, points: &[(i32, i32)]) -> bool {
+    let Some((left, top, right, bottom)) = target_client_rect(title) else {
+        return false;
+    };
+    points.iter().all(|&(x, y)| x >= left && x < right && y >= top && y < bottom)
+}
+
+// 目标窗口客户区的屏幕坐标 (left, top, right, bottom)
+#[cfg(windows)]
+fn target_client_rect(title: &str) -> Option<(i32, i32, i32, i32)> {
+    let hwnd = find_target_window(title)?;
+    
+    let mut rect = win32::Rect::default();
+    let mut origin = win32::Point::default();
+    let ok = unsafe { win32::GetClientRect(hwnd, &mut rect) != 0 && win32::ClientToScreen(hwnd, &mut origin) != 0 };
+    if !ok {
+        return None;
+    }
+    
+    // GetClientRect返回客户区坐标，左上角为(0, 0)
+    let (left, top) = (origin.x, origin.y);
+    Some((left, top, left + rect.right, top + rect.bottom))
+}
+
+#[cfg(not(windows))]
+fn target_client_rect(_title: &str) -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+// 当前平台无法查询窗口区域，不拦截点击
+#[cfg(not(windows))]
+fn points_inside_target_window(_title: &str, _points: &[(i32, i32)]) -> bool {
+    true
+}
+
+// Notifications
+// 在后台线程发送桌面通知，避免阻塞界面
+fn show_desktop_notification(body: String) {
+    thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .summary("SeriousClick")
+            .body(&body)
+            .show();
+    });
+}
+
+#[cfg(windows)]
+fn play_alert_sound() {
+    unsafe {
+        win32::MessageBeep(0); // MB_OK，系统默认提示音
+    }
+}
+
+#[cfg(not(windows))]
+fn play_alert_sound() {}
+
+// Virtual Desktop Bounds
+// 所有显示器组成的虚拟桌面范围 (left, top, right, bottom)，返回None表示无法查询
+#[cfg(windows)]
+fn virtual_desktop_bounds() -> Option<(i32, i32, i32, i32)> {
+    let (left, top, width, height) = unsafe {
+        (
+            win32::GetSystemMetrics(win32::SM_XVIRTUALSCREEN),
+            win32::GetSystemMetrics(win32::SM_YVIRTUALSCREEN),
+            win32::GetSystemMetrics(win32::SM_CXVIRTUALSCREEN),
+            win32::GetSystemMetrics(win32::SM_CYVIRTUALSCREEN),
+        )
+    };
+    if width <= 0 || height <= 0 {
+        return None;
+    }
+    Some((left, top, left + width, top + height))
+}
+
+#[cfg(not(windows))]
+fn virtual_desktop_bounds() -> Option<(i32, i32, i32, i32)> {
+    None
+}
+
+// 找出第一个落在虚拟桌面之外的坐标（例如所在显示器已断开）
+fn find_offscreen_point(points: &[(i32, i32)]) -> Option<(i32, i32)> {
+    let (left, top, right, bottom) = virtual_desktop_bounds()?;
+    points.iter()
+        .copied()
+        .find(|&(x, y)| x < left || x >= right || y < top || y >= bottom)
+}
+
+// 界面坐标（egui逻辑点）换算为物理像素。点击坐标都来自enigo，本身就是物理像素；
+// 界面上的坐标受set_pixels_per_point影响，必须经过这里换算后才能和点击坐标比较
+fn logical_to_physical(pos: egui::Pos2, pixels_per_point: f32) -> (i32, i32) {
+    ((pos.x * pixels_per_point).round() as i32, (pos.y * pixels_per_point).round() as i32)
+}
+
+// Input Backend
+// 点击循环只通过这个trait发送输入，基准测试使用不产生真实输入的实现
+trait InputBackend {
+    fn mouse_move_to(&mut self, x: i32, y: i32);
+    fn mouse_move_relative(&mut self, dx: i32, dy: i32);
+    fn mouse_down(&mut self, button: MouseButton);
+    fn mouse_up(&mut self, button: MouseButton);
+    fn mouse_click(&mut self, button: MouseButton);
+    fn mouse_location(&self) -> (i32, i32);
+    fn key_click(&mut self, key: enigo::Key);
+    fn key_down(&mut self, key: enigo::Key);
+    fn key_up(&mut self, key: enigo::Key);
+}
+
+impl InputBackend for Enigo {
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        MouseControllable::mouse_move_to(self, x, y);
+    }
+    
+    fn mouse_move_relative(&mut self, dx: i32, dy: i32) {
+        MouseControllable::mouse_move_relative(self, dx, dy);
+    }
+    
+    fn mouse_down(&mut self, button: MouseButton) {
+        MouseControllable::mouse_down(self, button);
+    }
+    
+    fn mouse_up(&mut self, button: MouseButton) {
+        MouseControllable::mouse_up(self, button);
+    }
+    
+    fn mouse_click(&mut self, button: MouseButton) {
+        MouseControllable::mouse_click(self, button);
+    }
+    
+    fn mouse_location(&self) -> (i32, i32) {
+        MouseControllable::mouse_location(self)
+    }
+    
+    fn key_click(&mut self, key: enigo::Key) {
+        KeyboardControllable::key_click(self, key);
+    }
+    
+    fn key_down(&mut self, key: enigo::Key) {
+        KeyboardControllable::key_down(self, key);
+    }
+    
+    fn key_up(&mut self, key: enigo::Key) {
+        KeyboardControllable::key_up(self, key);
+    }
+}
+
+// 只记录点击时间和移动到的坐标，不产生任何真实输入
+#[derive(Default)]
+struct MockInputBackend {
+    clicks: Vec<Instant>,
+    moves: Vec<(i32, i32)>,
+}
+
+impl InputBackend for MockInputBackend {
+    fn mouse_move_to(&mut self, x: i32, y: i32) {
+        self.moves.push((x, y));
+    }
+    
+    fn mouse_move_relative(&mut self, _dx: i32, _dy: i32) {}
+    
+    fn mouse_down(&mut self, _button: MouseButton) {}
+    
+    fn mouse_up(&mut self, _button: MouseButton) {
+        self.clicks.push(Instant::now());
+    }
+    
+    fn mouse_click(&mut self, _button: MouseButton) {
+        self.clicks.push(Instant::now());
+    }
+    
+    fn mouse_location(&self) -> (i32, i32) {
+        (0, 0)
+    }
+    
+    fn key_click(&mut self, _key: enigo::Key) {
+        self.clicks.push(Instant::now());
+    }
+    
+    fn key_down(&mut self, _key: enigo::Key) {
+        self.clicks.push(Instant::now());
+    }
+    
+    fn key_up(&mut self, _key: enigo::Key) {}
+}
+
+// 执行一次拖动：起点按下，插值移动到终点后松开
+// 中途停止时同样松开按键，返回false
+fn perform_drag<B: InputBackend>(backend: &mut B, button: MouseButton, from: (i32, i32), to: (i32, i32), state: &Arc<Mutex<ClickerState>>) -> bool {
+    backend.mouse_move_to(from.0, from.1);
+    backend.mouse_down(button);
+    
+    for step in 1..=DRAG_STEPS {
+        if !lock_state(state).is_running {
+            backend.mouse_up(button);
+            return false;
+        }
+        
+        let x = from.0 + (to.0 - from.0) * step / DRAG_STEPS;
+        let y = from.1 + (to.1 - from.1) * step / DRAG_STEPS;
+        backend.mouse_move_to(x, y);
+        thread::sleep(Duration::from_millis(DRAG_STEP_DELAY_MS));
+    }
+    
+    backend.mouse_up(button);
+    true
+}
+
+// 连续点击count下，组成双击或三击
+fn perform_multi_click<B: InputBackend>(backend: &mut B, button: MouseButton, count: u32, gap: Duration) {
+    for i in 0..count {
+        if i > 0 {
+            thread::sleep(gap);
+        }
+        backend.mouse_click(button);
+    }
+}
+
+// 先依次按下所有按键再按相反顺序松开，函数返回时不会留下按住的按键
+fn perform_combo<B: InputBackend>(backend: &mut B, buttons: &[MouseButton]) {
+    for &button in buttons {
+        backend.mouse_down(button);
+    }
+    for &button in buttons.iter().rev() {
+        backend.mouse_up(button);
+    }
+}
+
+// 按录制时的节奏回放路径，相邻采样点之间线性插值
+// 路径中没有录到点击时在终点点击一次；中途停止返回false
+fn perform_path<B: InputBackend>(backend: &mut B, button: MouseButton, path: &[PathPoint], state: &Arc<Mutex<ClickerState>>) -> bool {
+    let Some(first) = path.first() else {
+        return true;
+    };
+    
+    backend.mouse_move_to(first.x, first.y);
+    if first.click {
+        backend.mouse_click(button);
+    }
+    
+    for pair in path.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let span_ms = to.t_ms.saturating_sub(from.t_ms);
+        let steps = (span_ms / PATH_REPLAY_STEP_MS).max(1);
+        for step in 1..=steps {
+            if !lock_state(state).is_running {
+                return false;
+            }
+            
+            let x = from.x + ((to.x - from.x) as i64 * step as i64 / steps as i64) as i32;
+            let y = from.y + ((to.y - from.y) as i64 * step as i64 / steps as i64) as i32;
+            backend.mouse_move_to(x, y);
+            thread::sleep(Duration::from_millis(span_ms / steps));
+        }
+        
+        if to.click {
+            backend.mouse_click(button);
+        }
+    }
+    
+    if !path.iter().any(|point| point.click) {
+        backend.mouse_click(button);
+    }
+    true
+}
+
+// 录制鼠标轨迹，先等待倒计时让用户把鼠标移到起点
+fn record_mouse_path(countdown: Duration, duration: Duration) -> Vec<PathPoint> {
+    thread::sleep(countdown);
+    
+    let enigo = Enigo::new();
+    let start = Instant::now();
+    let mut path = Vec::new();
+    let mut was_down = false;
+    while start.elapsed() < duration {
+        let (x, y) = enigo.mouse_location();
+        let down = left_button_down().unwrap_or(false);
+        path.push(PathPoint {
+            x,
+            y,
+            t_ms: start.elapsed().as_millis() as u64,
+            click: down && !was_down,
+        });
+        was_down = down;
+        thread::sleep(PATH_SAMPLE_INTERVAL);
+    }
+    path
+}
+
+// Stuck Watchdog
+// 点击仍在继续但被监视的像素长时间没有变化时，认为目标程序已卡死
+const STUCK_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+struct StuckWatchdog {
+    timeout: Duration,
+    last_sample: Instant,
+    last_color: Option<[u8; 3]>,
+    last_change: Instant,
+}
+
+impl StuckWatchdog {
+    fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            last_sample: Instant::now(),
+            last_color: None,
+            last_change: Instant::now(),
+        }
+    }
+    
+    // 每次点击后调用，返回true表示画面已经超过timeout没有变化
+    fn sample(&mut self, point: Option<(i32, i32)>) -> bool {
+        if self.last_sample.elapsed() < STUCK_SAMPLE_INTERVAL {
+            return false;
+        }
+        self.last_sample = Instant::now();
+        
+        let Some(color) = point.and_then(|(x, y)| read_screen_pixel(x, y)) else {
+            // 读不到像素时无法判断，重新计时
+            self.last_color = None;
+            self.last_change = Instant::now();
+            return false;
+        };
+        
+        if self.last_color != Some(color) {
+            self.last_color = Some(color);
+            self.last_change = Instant::now();
+            return false;
+        }
+        self.last_change.elapsed() >= self.timeout
+    }
+}
+
+// 连续模式的速率控制器
+// 每秒测量一次实际CPS，按比例修正点击间隔，抵消系统负载带来的偏差
+const CPS_CONTROLLER_GAIN: f64 = 0.5;
+const CPS_MEASURE_WINDOW: Duration = Duration::from_secs(1);
+
+struct CpsController {
+    target_cps: u32,
+    delay_us: f64,
+    window_start: Instant,
+    window_clicks: u32,
+}
+
+impl CpsController {
+    fn new(target_cps: u32) -> Self {
+        let target_cps = target_cps.max(1);
+        Self {
+            target_cps,
+            delay_us: 1_000_000.0 / target_cps as f64,
+            window_start: Instant::now(),
+            window_clicks: 0,
+        }
+    }
+    
+    fn delay(&mut self, target_cps: u32) -> Duration {
+        // 目标速率被修改时重新开始收敛
+        if target_cps.max(1) != self.target_cps {
+            *self = Self::new(target_cps);
+        }
+        Duration::from_micros(self.delay_us as u64)
+    }
+    
+    fn record_click(&mut self) {
+        self.window_clicks += 1;
+        let elapsed = self.window_start.elapsed();
+        if elapsed < CPS_MEASURE_WINDOW {
+            return;
+        }
+        
+        let target_period_us = 1_000_000.0 / self.target_cps as f64;
+        let actual_period_us = elapsed.as_micros() as f64 / self.window_clicks as f64;
+        self.delay_us = (self.delay_us + CPS_CONTROLLER_GAIN * (target_period_us - actual_period_us))
+            .clamp(0.0, target_period_us);
+        
+        self.window_start = Instant::now();
+        self.window_clicks = 0;
+    }
+}
+
+// 分段睡眠，停止后最多延迟一个分段就返回，长间隔也不会卡住stop_clicker
+const SLEEP_SLICE: Duration = Duration::from_millis(50);
+
+// 暂停期间检查是否继续的间隔
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// 高精度计时时最后这段不睡眠而是忙等，避开系统定时器的误差
+const SPIN_THRESHOLD: Duration = Duration::from_micros(1500);
+
+fn sleep_while_running(shared_state: &Arc<Mutex<ClickerState>>, delay: Duration) {
+    let deadline = Instant::now() + delay;
+    let precise = lock_state(shared_state).high_precision_timing;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return;
+        }
+        if precise && remaining <= SPIN_THRESHOLD {
+            while Instant::now() < deadline {
+                std::hint::spin_loop();
+            }
+            return;
+        }
+        let sleep_for = if precise { remaining - SPIN_THRESHOLD } else { remaining };
+        thread::sleep(sleep_for.min(SLEEP_SLICE));
+        if remaining > SLEEP_SLICE && !lock_state(shared_state).is_running {
+            return;
+        }
+    }
+}
+
+// 一轮达到上限后，还有剩余轮数时停顿loop_gap_ms再开始下一轮；返回false表示所有轮数已完成
+fn start_next_loop(shared_state: &Arc<Mutex<ClickerState>>) -> bool {
+    let gap = {
+        let mut state = lock_state(shared_state);
+        if state.loops_done + 1 >= state.loop_count.max(1) {
+            return false;
+        }
+        state.loops_done += 1;
+        Duration::from_millis(state.loop_gap_ms)
+    };
+    sleep_while_running(shared_state, gap);
+    
+    // 停顿期间被停止时由循环开头退出
+    let mut state = lock_state(shared_state);
+    state.loop_start_count = state.click_count;
+    state.loop_start_elapsed = state.start_time.map_or(Duration::ZERO, |start_time| start_time.elapsed());
+    true
+}
+
+// 松开自动重复时按住的按键，停止、暂停或一轮结束时调用
+fn release_held_key<B: InputBackend>(backend: &mut B, shared_state: &Arc<Mutex<ClickerState>>) {
+    let held_key = lock_state(shared_state).held_key.take();
+    if let Some(key) = held_key {
+        backend.key_up(key);
+    }
+}
+
+// Click Loop
+// 运行直到is_running被清除，统计快照通过回调交给调用方（界面线程或基准测试）
+fn run_click_loop<B: InputBackend>(backend: &mut B, shared_state: &Arc<Mutex<ClickerState>>, mut on_stats: impl FnMut(ClickerStats)) {
+    let mut last_stats_sent = Instant::now();
+    let (mut rng, mut pattern_index, mut cps_controller) = {
+        let state = lock_state(shared_state);
+        // 设置了种子时随机序列可复现，便于测试和排查问题
+        let mut rng = match state.rng_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        // 随机起始位置，让模式的前几次点击不可预测
+        let pattern_index = if state.randomize_start_index && !state.pattern_intervals.is_empty() {
+            rng.gen_range(0..state.pattern_intervals.len())
+        } else {
+            0
+        };
+        (rng, pattern_index, CpsController::new(state.target_cps))
+    };
+    let mut stuck_watchdog = {
+        let state = lock_state(shared_state);
+        let has_condition = state.pixel_condition.is_some() || !state.target_window_title.is_empty();
+        (state.stuck_timeout_secs > 0 && has_condition).then(|| StuckWatchdog::new(Duration::from_secs(state.stuck_timeout_secs)))
+    };
+    let mut move_index = 0;
+    let mut point_index = 0;
+    let mut burst_index = 0; // 当前这组已经点了几下，每次运行从0开始
+    let precise = lock_state(shared_state).high_precision_timing;
+    if precise {
+        set_fine_timer_resolution(true);
+    }
+    
+    loop {
+        // 暂停时不点击，线程保留序列位置，继续后接着点击
+        let (running, paused) = {
+            let state = lock_state(shared_state);
+            (state.is_running, state.paused_at.is_some())
+        };
+        if !running {
+            break;
+        }
+        if paused {
+            // 暂停时松开按键，继续后重新等待首次延迟
+            release_held_key(backend, shared_state);
+            sleep_while_running(shared_state, PAUSE_POLL_INTERVAL);
+            // 暂停期间画面不变是正常的，继续后重新计时
+            if let Some(watchdog) = &mut stuck_watchdog {
+                *watchdog = StuckWatchdog::new(watchdog.timeout);
+            }
+            continue;
+        }
+        
+        // 达到时长上限后停止，和点击上限先到者生效；放在最前面，等待像素条件时也会检查
+        if lock_state(shared_state).time_remaining() == Some(Duration::ZERO) {
+            // 还有剩余轮数时停顿后开始下一轮
+            release_held_key(backend, shared_state);
+            if start_next_loop(shared_state) {
+                continue;
+            }
+            let mut state = lock_state(shared_state);
+            let secs = state.time_limit.map_or(0, |limit| limit.as_secs());
+            state.is_running = false;
+            state.run_completed = true;
+            state.stop_reason = Some(StopReason::TimeLimit(secs));
+            break;
+        }
+        
+        // 像素条件：不匹配时只重新采样，不点击
+        let pixel_condition = lock_state(shared_state).pixel_condition;
+        if let Some(condition) = pixel_condition {
+            match read_screen_pixel(condition.x, condition.y) {
+                Some(color) if condition.matches(color) => {}
+                Some(_) => {
+                    sleep_while_running(shared_state, PIXEL_SAMPLE_INTERVAL);
+                    if !lock_state(shared_state).is_running {
+                        break;
+                    }
+                    continue;
+                }
+                None => {
+                    let mut state = lock_state(shared_state);
+                    state.is_running = false;
+                    state.stop_reason = Some(StopReason::PixelUnavailable);
+                    break;
+                }
+            }
+        }
+        
+        let (should_continue, delay, button, mode, pattern, action, relative_move, cursor_offset, target_position, sequence_point, clicks, key, key_repeat, path, combo, post_click_delay, multi_click_gap) = {
+            let mut state = lock_state(shared_state);
+            if !state.is_running {
+                break;
+            }
+            
+            // 目标窗口看门狗：窗口消失后停止，避免点到后面的程序
+            if state.stop_on_window_lost
+                && !state.target_window_title.is_empty()
+                && target_window_exists(&state.target_window_title) == Some(false)
+            {
+                state.is_running = false;
+                state.stop_reason = Some(StopReason::WindowLost(state.target_window_title.clone()));
+                break;
+            }
+            
+            // 锁屏或屏保时停止，避免一直点击锁屏界面
+            if state.stop_on_session_lock && session_locked() == Some(true) {
+                state.is_running = false;
+                state.stop_reason = Some(StopReason::SessionLocked);
+                break;
+            }
+            
+            // 显示器断开后坐标可能失效，停止而不是点击到被截断的位置
+            if let Some((x, y)) = find_offscreen_point(&state.target_points()) {
+                state.is_running = false;
+                state.stop_reason = Some(StopReason::Offscreen(x, y));
+                break;
+            }
+            
+            // 坐标超出目标窗口客户区（窗口被移动或缩小）时跳过本次点击
+            let target_points = state.target_points();
+            let inside_window = !state.restrict_to_window_client
+                || state.target_window_title.is_empty()
+                || target_points.is_empty()
+                || points_inside_target_window(&state.target_window_title, &target_points);
+            if !inside_window {
+                state.skipped_clicks += 1;
+            }
+            
+            let button = state.mouse_button.to_enigo_button();
+            let mode = state.click_mode;
+            
+            let delay = match mode {
+                ClickMode::FixedInterval => match state.rate_cps {
+                    Some(cps) if cps > 0.0 => Duration::from_secs_f64(1.0 / cps),
+                    _ => Duration::from_millis(state.fixed_interval_ms),
+                },
+                ClickMode::RandomInterval => {
+                    // 状态在别处被改乱时gen_range会panic，这里再保证一次上下界有序
+                    let (min_ms, max_ms) = random_interval_bounds(state.min_random_interval_ms, state.max_random_interval_ms);
+                    Duration::from_millis(rng.gen_range(min_ms..=max_ms))
+                },
+                ClickMode::Continuous => cps_controller.delay(state.target_cps),
+                ClickMode::Pattern => {
+                    if state.pattern_intervals.is_empty() {
+                        Duration::from_millis(100) // 默认值
+                    } else {
+                        Duration::from_millis(state.pattern_intervals[pattern_index])
+                    }
+                },
+                ClickMode::Ramp => {
+                    let elapsed = state.start_time.map_or(Duration::ZERO, |start_time| start_time.elapsed());
+                    let interval_ms = ramp_interval_ms(state.ramp_start_ms, state.ramp_end_ms, state.ramp_duration_ms, elapsed);
+                    Duration::from_secs_f64(interval_ms.max(0.0) / 1000.0)
+                },
+                ClickMode::Burst => {
+                    // 本次点击后这一组是否点完，点完就停顿并开始新的一组
+                    let clicks_done = burst_index + 1;
+                    burst_index = clicks_done % state.burst_size.max(1);
+                    if burst_index == 0 {
+                        Duration::from_millis(state.burst_pause_ms)
+                    } else {
+                        Duration::from_millis(state.burst_interval_ms)
+                    }
+                },
+            };
+            
+            // 模拟按住按键：首次按下后等待较长的首次延迟，之后按较短的重复间隔
+            let key_repeat = state.action_type == ActionType::KeyPress && state.key_repeat;
+            let delay = match (key_repeat, state.held_key.is_some()) {
+                (true, false) => Duration::from_millis(state.key_repeat_delay_ms),
+                (true, true) => Duration::from_millis(state.key_repeat_interval_ms),
+                (false, _) => delay,
+            };
+            
+            // 安全上限：误改的配置也不能以超过上限的速率发送输入
+            let min_delay = Duration::from_secs_f64(1.0 / effective_max_cps(state.max_cps, state.app_max_cps));
+            state.rate_limited = delay < min_delay;
+            let delay = delay.max(min_delay);
+            // 间隔很长时不要睡过时长上限
+            let delay = state.time_remaining().map_or(delay, |remaining| delay.min(remaining));
+            
+            let pattern = state.pattern_intervals.clone();
+            let relative_move = if state.relative_moves.is_empty() {
+                None
+            } else {
+                let delta = state.relative_moves[move_index % state.relative_moves.len()];
+                move_index = (move_index + 1) % state.relative_moves.len();
+                Some(delta)
+            };
+            let path = if state.action_type == ActionType::FollowPath { state.path.clone() } else { Vec::new() };
+            // 坐标序列和模式点击的间隔序列一样循环前进
+            let sequence_point = if state.click_points.is_empty() {
+                None
+            } else {
+                let point = state.click_points[point_index % state.click_points.len()];
+                point_index = (point_index + 1) % state.click_points.len();
+                Some(point)
+            };
+            let combo: Vec<MouseButton> = if state.combo_buttons.is_empty() {
+                vec![button]
+            } else {
+                state.combo_buttons.iter().map(|button| button.to_enigo_button()).collect()
+            };
+            let post_click_delay = Duration::from_millis(state.post_click_delay_ms);
+            let multi_click_gap = Duration::from_millis(state.multi_click_gap_ms);
+            let target_position = state.target_position.map(|(x, y)| {
+                let (dx, dy) = jitter_offset(&mut rng, state.position_jitter_px);
+                (x + dx, y + dy)
+            });
+            let key = if state.action_type == ActionType::KeyPress { enigo_key(&state.press_key) } else { None };
+            // 每次动作的点击数，不超过点击上限剩余的次数
+            let mut clicks = match state.action_type {
+                ActionType::MouseClick | ActionType::PointSequence => state.clicks_per_action.clamp(1, MAX_CLICKS_PER_ACTION),
+                _ => 1,
+            };
+            if let Some(limit) = state.click_limit {
+                let remaining = limit.saturating_sub(state.click_count.saturating_sub(state.loop_start_count)).max(1);
+                clicks = clicks.min(remaining.min(u32::MAX as u64) as u32);
+            }
+            (inside_window, delay, button, mode, pattern, state.action_type, relative_move, state.cursor_offset, target_position, sequence_point, clicks, key, key_repeat, path, combo, post_click_delay, multi_click_gap)
+        };
+        
+        if should_continue {
+            // 点击前按序列相对移动鼠标
+            if let Some((dx, dy)) = relative_move {
+                backend.mouse_move_relative(dx, dy);
+            }
+            
+            // 执行点击
+            match action {
+                ActionType::MouseClick if cursor_offset == (0, 0) && target_position.is_none() => perform_multi_click(backend, button, clicks, multi_click_gap),
+                ActionType::MouseClick => {
+                    // 在固定坐标或偏移处点击后移回原位，鼠标仍然跟随用户
+                    let (x, y) = backend.mouse_location();
+                    let (base_x, base_y) = target_position.unwrap_or((x, y));
+                    backend.mouse_move_to(base_x + cursor_offset.0, base_y + cursor_offset.1);
+                    perform_multi_click(backend, button, clicks, multi_click_gap);
+                    backend.mouse_move_to(x, y);
+                }
+                ActionType::Drag { from, to } => {
+                    if !perform_drag(backend, button, from, to, shared_state) {
+                        break;
+                    }
+                }
+                ActionType::FollowPath => {
+                    if !perform_path(backend, button, &path, shared_state) {
+                        break;
+                    }
+                }
+                ActionType::ButtonCombo => perform_combo(backend, &combo),
+                ActionType::PointSequence => {
+                    if let Some((x, y)) = sequence_point {
+                        backend.mouse_move_to(x, y);
+                        perform_multi_click(backend, button, clicks, multi_click_gap);
+                    }
+                }
+                ActionType::KeyPress => {
+                    if let Some(key) = key {
+                        if key_repeat {
+                            // 按住不松开并重复发送按下事件，和物理按键的自动重复一致
+                            backend.key_down(key);
+                            lock_state(shared_state).held_key = Some(key);
+                        } else {
+                            backend.key_click(key);
+                        }
+                    }
+                }
+            }
+            
+            // 点击后的稳定时间，和下面的间隔分开计算
+            if !post_click_delay.is_zero() {
+                sleep_while_running(shared_state, post_click_delay);
+            }
+            
+            // 更新状态
+            let mut state = lock_state(shared_state);
+            let now = Instant::now();
+            state.click_count += clicks as u64;
+            state.last_click_time = Some(now);
+            state.recent_clicks.extend(std::iter::repeat(now).take(clicks as usize));
+            while state.recent_clicks.front().is_some_and(|time| now.duration_since(*time) >= RATE_WINDOW) {
+                state.recent_clicks.pop_front();
+            }
+            
+            // 达到点击上限后停止
+            if let Some(limit) = state.click_limit {
+                if state.click_count.saturating_sub(state.loop_start_count) >= limit {
+                    drop(state);
+                    release_held_key(backend, shared_state);
+                    if start_next_loop(shared_state) {
+                        continue;
+                    }
+                    let mut state = lock_state(shared_state);
+                    state.is_running = false;
+                    state.run_completed = true;
+                    state.stop_reason = Some(StopReason::ClickLimit(limit));
+                    break;
+                }
+            }
+            
+            let watch_point = if stuck_watchdog.is_some() { state.stuck_watch_point() } else { None };
+            let stats = state.stats();
+            drop(state);
+            
+            // 点击在继续但画面一直不变，可能是目标程序卡死了
+            if let Some(watchdog) = &mut stuck_watchdog {
+                if watchdog.sample(watch_point) {
+                    let mut state = lock_state(shared_state);
+                    state.is_running = false;
+                    state.stop_reason = Some(StopReason::Stuck(state.stuck_timeout_secs));
+                    break;
+                }
+            }
+            
+            // 限制发送频率，只在有新数据时请求重绘
+            if last_stats_sent.elapsed() >= STATS_SEND_INTERVAL {
+                on_stats(stats);
+                last_stats_sent = Instant::now();
+            }
+            
+            // 更新模式索引
+            if mode == ClickMode::Pattern && !pattern.is_empty() {
+                pattern_index = (pattern_index + 1) % pattern.len();
+            }
+            
+            if mode == ClickMode::Continuous {
+                cps_controller.record_click();
+            }
+            
+            // 等待下一次点击
+            sleep_while_running(shared_state, delay);
+        } else {
+            sleep_while_running(shared_state, delay);
+        }
+    }
+    
+    release_held_key(backend, shared_state);
+    if precise {
+        set_fine_timer_resolution(false);
+    }
+    
+    // 线程结束时发送最终快照，附带自行停止的原因
+    let mut state = lock_state(shared_state);
+    let mut stats = state.stats();
+    stats.stop_reason = state.stop_reason.take();
+    drop(state);
+    on_stats(stats);
+}
+
+// Config Directory
+// 启动时确定一次：首选系统配置目录（便携模式下为程序所在目录），不可写时依次退回到程序所在目录和临时目录
+struct ConfigLocation {
+    dir: PathBuf,
+    fallback_from: Option<PathBuf>, // 不可写的首选目录，None表示使用的就是首选目录
+    portable: bool,
+}
+
+static CONFIG_LOCATION: Lazy<ConfigLocation> = Lazy::new(resolve_config_location);
+
+// 程序旁边有这个文件时进入便携模式，配置和设置保存在程序旁边
+const PORTABLE_MARKER: &str = "portable.txt";
+
+fn standard_config_dir() -> PathBuf {
+    if let Some(proj_dirs) = directories::ProjectDirs::from("com", "SeriousClick", "SeriousClick") {
+        proj_dirs.config_dir().to_path_buf()
+    } else {
+        PathBuf::from(".").join(".config")
+    }
+}
+
+fn portable_config_dir() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    exe_dir.join(PORTABLE_MARKER).exists().then_some(exe_dir)
+}
+
+fn resolve_config_location() -> ConfigLocation {
+    let portable_dir = portable_config_dir();
+    let portable = portable_dir.is_some();
+    let preferred = portable_dir.unwrap_or_else(standard_config_dir);
+    
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join("SeriousClick-config")));
+    let temp_dir = std::env::temp_dir().join("SeriousClick");
+    let fallbacks: Vec<PathBuf> = exe_dir.into_iter().chain(std::iter::once(temp_dir)).collect();
+    
+    let (dir, fallback_from) = choose_config_dir(preferred, &fallbacks);
+    ConfigLocation { dir, fallback_from, portable }
+}
+
+// 首选目录可写时直接使用，否则取第一个可写的备选目录，都不可写时用最后一个
+fn choose_config_dir(preferred: PathBuf, fallbacks: &[PathBuf]) -> (PathBuf, Option<PathBuf>) {
+    if dir_is_writable(&preferred) {
+        return (preferred, None);
+    }
+    
+    let dir = fallbacks
+        .iter()
+        .find(|dir| dir_is_writable(dir))
+        .or(fallbacks.last())
+        .cloned()
+        .unwrap_or_else(|| preferred.clone());
+    (dir, Some(preferred))
+}
+
+// 创建目录并写入一个探测文件，确认确实可以保存
+fn dir_is_writable(dir: &std::path::Path) -> bool {
+    if fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".write_test");
+    let writable = fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    writable
+}
+
+// Config File Path
+fn get_config_dir() -> PathBuf {
+    CONFIG_LOCATION.dir.join("configs.json")
+}
+
+// 配置文件的修改时间，用于发现外部修改
+const CONFIG_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+fn config_file_modified() -> Option<SystemTime> {
+    fs::metadata(get_config_dir()).and_then(|metadata| metadata.modified()).ok()
+}
+
+// Settings File Path (保存在配置文件旁边)
+fn get_settings_path() -> PathBuf {
+    get_config_dir().with_file_name("settings.json")
+}
+
+// Application Settings
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+struct AppSettings {
+    self_test_on_startup: bool, // 启动时检查输入注入是否可用
+    sort_column: Option<GridColumn>, // 配置表格排序列，None为原始顺序
+    sort_descending: bool,
+    hidden_columns: Vec<GridColumn>,
+    notify_on_completion: bool, // 有上限的运行自行完成时发送桌面通知
+    sound_on_completion: bool,  // 同时播放提示音
+    reset_count_on_start: bool, // 每次开始时清零点击数，关闭后在本次会话内累计
+    reload_configs_on_change: bool, // configs.json被外部修改时自动重新加载
+    max_cps: f64, // 全局CPS上限，所有配置和模式都生效
+    freeze_edits_while_running: bool, // 运行中禁止修改编辑窗口中的配置
+    delete_key_deletes_config: bool, // 按Delete键删除选中的配置（仍需确认）
+    stop_on_session_lock: bool, // 锁屏或屏保启动时停止点击
+    high_precision_timing: bool, // 点击间隔用睡眠加忙等，更准但更耗CPU
+    resume_on_unlock: bool,     // 因锁屏停止后，解锁时自动重新开始
+    hotkeys_enabled: bool, // 热键总开关，启动时按上次的选择恢复
+    suppress_hotkeys_in_dialogs: bool, // 对话框打开时忽略热键
+    suppress_hotkeys_while_typing: bool, // 文本框有键盘焦点时忽略热键
+    hotkey_feedback_sound: bool, // 收到热键时播放提示音，窗口在后台时也能确认
+    hotkey_feedback_notification: bool, // 收到热键时发送桌面通知
+    emergency_exit_enabled: bool, // Ctrl+Alt+F8 强制退出整个程序
+    hotkey_modifiers: u32, // 开始/停止热键的修饰键（hotkey::modifiers）
+    hotkey_keycode: u32,   // 开始/停止热键的虚拟键码
+    allow_continuous_mode: bool, // 连续模式速度很快，新用户默认不显示
+    allow_multiple_instances: bool, // 允许同时运行多个SeriousClick
+    cps_decimals: usize, // CPS显示的小数位数
+    rng_seed: Option<u64>, // 随机间隔使用的固定种子，用于复现问题
+    command_server_enabled: bool, // 开启本地命令端口
+    command_port: u16,
+    record_usage_history: bool, // 在本地记录每次运行，用于使用统计
+    last_config_index: usize, // 上次选中的配置，下次启动时恢复
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            self_test_on_startup: false,
+            sort_column: None,
+            sort_descending: false,
+            hidden_columns: Vec::new(),
+            notify_on_completion: false,
+            sound_on_completion: false,
+            reset_count_on_start: true,
+            reload_configs_on_change: true,
+            max_cps: DEFAULT_MAX_CPS,
+            freeze_edits_while_running: false,
+            delete_key_deletes_config: true,
+            stop_on_session_lock: false,
+            high_precision_timing: false,
+            resume_on_unlock: false,
+            hotkeys_enabled: false,
+            suppress_hotkeys_in_dialogs: true,
+            suppress_hotkeys_while_typing: true,
+            hotkey_feedback_sound: false,
+            hotkey_feedback_notification: false,
+            emergency_exit_enabled: false,
+            hotkey_modifiers: DEFAULT_HOTKEY_MODIFIERS,
+            hotkey_keycode: DEFAULT_HOTKEY_KEYCODE,
+            allow_continuous_mode: false,
+            allow_multiple_instances: false,
+            cps_decimals: DEFAULT_CPS_DECIMALS,
+            rng_seed: None,
+            command_server_enabled: false,
+            command_port: DEFAULT_COMMAND_PORT,
+            record_usage_history: false,
+            last_config_index: 0,
+        }
+    }
+}
+
+impl AppSettings {
+    fn load() -> Self {
+        fs::read_to_string(get_settings_path())
+            .ok()
+            .and_then(|settings_str| serde_json::from_str(&settings_str).ok())
+            .unwrap_or_default()
+    }
+    
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let settings_str = serde_json::to_string_pretty(self)?;
+        fs::write(get_settings_path(), settings_str)?;
+        Ok(())
+    }
+}
+
+// Usage History
+// 只保存在本地的运行记录，供使用统计窗口汇总，不会发送到任何地方
+const MAX_USAGE_RECORDS: usize = 10_000;
+const WEEKDAY_NAMES: [&str; 7] = ["日", "一", "二", "三", "四", "五", "六"];
+
+fn get_usage_history_path() -> PathBuf {
+    get_config_dir().with_file_name("usage_history.json")
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct SessionRecord {
+    config_name: String,
+    started_at: i64, // Unix时间戳（秒）
+    duration_secs: u64,
+    clicks: u64,
+}
+
+impl SessionRecord {
+    // 0为星期日，按UTC计算（1970-01-01是星期四）
+    fn weekday(&self) -> usize {
+        (self.started_at.div_euclid(86400) + 4).rem_euclid(7) as usize
+    }
+}
+
+fn load_usage_history() -> Vec<SessionRecord> {
+    fs::read_to_string(get_usage_history_path())
+        .ok()
+        .and_then(|history_str| serde_json::from_str(&history_str).ok())
+        .unwrap_or_default()
+}
+
+fn save_usage_history(history: &[SessionRecord]) -> Result<(), Box<dyn std::error::Error>> {
+    let history_str = serde_json::to_string(history)?;
+    fs::write(get_usage_history_path(), history_str)?;
+    Ok(())
+}
+
+struct UsageSummary {
+    sessions: usize,
+    total_clicks: u64,
+    average_duration_secs: u64,
+    most_used_config: Option<(String, usize)>, // 配置名和运行次数
+    sessions_by_weekday: [usize; 7],
+}
+
+fn summarize_usage(history: &[SessionRecord]) -> UsageSummary {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    let mut sessions_by_weekday = [0; 7];
+    for record in history {
+        match counts.iter_mut().find(|(name, _)| *name == record.config_name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((record.config_name.as_str(), 1)),
+        }
+        sessions_by_weekday[record.weekday()] += 1;
+    }
+    
+    let total_duration: u64 = history.iter().map(|record| record.duration_secs).sum();
+    UsageSummary {
+        sessions: history.len(),
+        total_clicks: history.iter().map(|record| record.clicks).sum(),
+        average_duration_secs: if history.is_empty() { 0 } else { total_duration / history.len() as u64 },
+        most_used_config: counts.into_iter()
+            .max_by_key(|&(_, count)| count)
+            .map(|(name, count)| (name.to_string(), count)),
+        sessions_by_weekday,
+    }
+}
+
+// 按星期几的运行次数柱状图
+fn draw_weekday_chart(ui: &mut egui::Ui, sessions_by_weekday: &[usize; 7]) {
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(280.0, 120.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let max = sessions_by_weekday.iter().copied().max().unwrap_or(0).max(1);
+    let label_height = 16.0;
+    let slot_width = rect.width() / 7.0;
+    
+    for (day, &count) in sessions_by_weekday.iter().enumerate() {
+        let left = rect.left() + slot_width * day as f32;
+        let bar_height = (rect.height() - 2.0 * label_height) * count as f32 / max as f32;
+        let bottom = rect.bottom() - label_height;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + slot_width * 0.2, bottom - bar_height),
+            egui::pos2(left + slot_width * 0.8, bottom),
+        );
+        painter.rect_filled(bar, 2.0, Color32::from_rgb(60, 100, 150));
+        
+        let font = egui::FontId::proportional(12.0);
+        painter.text(egui::pos2(bar.center().x, bar.top() - 2.0), egui::Align2::CENTER_BOTTOM, count.to_string(), font.clone(), Color32::LIGHT_GRAY);
+        painter.text(egui::pos2(bar.center().x, rect.bottom()), egui::Align2::CENTER_BOTTOM, WEEKDAY_NAMES[day], font, Color32::LIGHT_GRAY);
+    }
+}
+
+// Input Self-Test
+// 把鼠标移动1像素再移回，通过mouse_location确认注入的输入确实生效
+fn run_input_self_test() -> Result<(), String> {
+    let mut enigo = Enigo::new();
+    let (x, y) = enigo.mouse_location();
+    let expected = if x > 0 { (x - 1, y) } else { (x + 1, y) };
+    
+    MouseControllable::mouse_move_to(&mut enigo, expected.0, expected.1);
+    thread::sleep(Duration::from_millis(50));
+    let actual = enigo.mouse_location();
+    MouseControllable::mouse_move_to(&mut enigo, x, y);
+    
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!("鼠标应移动到 ({}, {})，实际位于 ({}, {})", expected.0, expected.1, actual.0, actual.1))
+    }
+}
+
+// Benchmark
+// --benchmark 在无界面环境下用MockInputBackend跑每种模式，输出每行一个JSON结果
+const BENCHMARK_FLAG: &str = "--benchmark";
+const BENCHMARK_DURATION: Duration = Duration::from_secs(2);
+
+#[derive(Serialize)]
+struct BenchmarkResult {
+    mode: &'static str,
+    clicks: usize,
+    duration_s: f64,
+    cps: f64,
+    mean_interval_ms: f64,
+    jitter_ms: f64, // 点击间隔的标准差
+    max_interval_ms: f64,
+}
+
+fn benchmark_mode(mode: ClickMode) -> BenchmarkResult {
+    let mut state = ClickerState::default();
+    state.click_mode = mode;
+    state.is_running = true;
+    state.start_time = Some(Instant::now());
+    let state = Arc::new(Mutex::new(state));
+    
+    let state_clone = Arc::clone(&state);
+    let handle = thread::spawn(move || {
+        let mut backend = MockInputBackend::default();
+        run_click_loop(&mut backend, &state_clone, |_| {});
+        backend
+    });
+    
+    let started = Instant::now();
+    thread::sleep(BENCHMARK_DURATION);
+    lock_state(&state).is_running = false;
+    let backend = handle.join().expect("benchmark click loop panicked");
+    let duration_s = started.elapsed().as_secs_f64();
+    
+    let intervals: Vec<f64> = backend.clicks
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).as_secs_f64() * 1000.0)
+        .collect();
+    let mean_interval_ms = if intervals.is_empty() {
+        0.0
+    } else {
+        intervals.iter().sum::<f64>() / intervals.len() as f64
+    };
+    let jitter_ms = if intervals.is_empty() {
+        0.0
+    } else {
+        (intervals.iter().map(|i| (i - mean_interval_ms).powi(2)).sum::<f64>() / intervals.len() as f64).sqrt()
+    };
+    
+    BenchmarkResult {
+        mode: mode.name(),
+        clicks: backend.clicks.len(),
+        duration_s,
+        cps: backend.clicks.len() as f64 / duration_s,
+        mean_interval_ms,
+        jitter_ms,
+        max_interval_ms: intervals.iter().copied().fold(0.0, f64::max),
+    }
+}
+
+fn run_benchmark() {
+    for mode in ClickMode::all() {
+        let result = benchmark_mode(mode);
+        match serde_json::to_string(&result) {
+            Ok(line) => println!("{}", line),
+            Err(e) => eprintln!("Failed to serialize benchmark result: {}", e),
+        }
+    }
+}
+
+// Headless Detection
+// 没有X11/Wayland会话时enigo无法注入输入，eframe也打不开窗口
+#[cfg(all(unix, not(target_os = "macos")))]
+fn display_available() -> bool {
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|var| std::env::var_os(var).is_some_and(|value| !value.is_empty()))
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+fn display_available() -> bool {
+    true
+}
+
+// 诊断信息中的显示服务器，Linux下X11和Wayland的输入注入能力不同
+#[cfg(all(unix, not(target_os = "macos")))]
+fn display_server() -> String {
+    let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+    match (var("XDG_SESSION_TYPE"), var("WAYLAND_DISPLAY"), var("DISPLAY")) {
+        (Some(session), _, _) => session,
+        (None, Some(_), _) => "wayland".to_string(),
+        (None, None, Some(_)) => "x11".to_string(),
+        (None, None, None) => "无".to_string(),
+    }
+}
+
+#[cfg(windows)]
+fn display_server() -> String {
+    "Windows 桌面".to_string()
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos")))))]
+fn display_server() -> String {
+    "系统默认".to_string()
+}
+
+// Command Server
+// 本地TCP行协议，供Stream Deck插件或脚本控制：
+//   START / STOP / TOGGLE / SELECT <配置名> / STATUS
+// 每条命令回复一行，以 "OK " 或 "ERR " 开头
+const DEFAULT_COMMAND_PORT: u16 = 48620;
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const COMMAND_REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+
+// 接受连接失败的原因，由界面线程取出后写入状态栏
+static COMMAND_SERVER_ERROR: Lazy<Arc<Mutex<Option<String>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
+
+#[derive(Debug, PartialEq, Clone)]
+enum RemoteCommand {
+    Start,
+    Stop,
+    Toggle,
+    Select(String),
+    Status,
+}
+
+impl RemoteCommand {
+    fn parse(line: &str) -> Result<RemoteCommand, String> {
+        let line = line.trim();
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+        
+        match command.to_ascii_uppercase().as_str() {
+            "START" => Ok(RemoteCommand::Start),
+            "STOP" => Ok(RemoteCommand::Stop),
+            "TOGGLE" => Ok(RemoteCommand::Toggle),
+            "STATUS" => Ok(RemoteCommand::Status),
+            "SELECT" if !argument.is_empty() => Ok(RemoteCommand::Select(argument.to_string())),
+            "SELECT" => Err("SELECT needs a config name".to_string()),
+            _ => Err(format!("unknown command: {}", command)),
+        }
+    }
+}
+
+// 命令连同回复通道一起交给界面线程处理
+type RemoteRequest = (RemoteCommand, mpsc::Sender<String>);
+
+fn spawn_command_server(
+    port: u16,
+    ctx: egui::Context,
+    requests: mpsc::Sender<RemoteRequest>,
+    running: Arc<AtomicBool>,
+) -> std::io::Result<thread::JoinHandle<()>> {
+    // 只监听本机，避免局域网内的其他机器控制鼠标
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    listener.set_nonblocking(true)?;
+    
+    Ok(thread::spawn(move || {
+        // 连续失败只报告第一次，避免刷满状态历史
+        let mut failing = false;
+        while running.load(Ordering::Relaxed) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    failing = false;
+                    let ctx = ctx.clone();
+                    let requests = requests.clone();
+                    let running = Arc::clone(&running);
+                    thread::spawn(move || handle_command_connection(stream, ctx, requests, running));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(COMMAND_POLL_INTERVAL),
+                Err(e) => {
+                    if !failing {
+                        failing = true;
+                        *COMMAND_SERVER_ERROR.lock().unwrap() = Some(e.to_string());
+                        ctx.request_repaint();
+                    }
+                    thread::sleep(COMMAND_POLL_INTERVAL);
+                }
+            }
+        }
+    }))
+}
+
+fn handle_command_connection(stream: TcpStream, ctx: egui::Context, requests: mpsc::Sender<RemoteRequest>, running: Arc<AtomicBool>) {
+    let _ = stream.set_nonblocking(false);
+    // 读取超时用于定期检查服务是否已关闭
+    let _ = stream.set_read_timeout(Some(Duration::from_millis(500)));
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    
+    while running.load(Ordering::Relaxed) {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) => return, // 客户端断开
+            Ok(_) => {}
+            Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => continue,
+            Err(_) => return,
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        
+        let reply = match RemoteCommand::parse(&line) {
+            Ok(command) => {
+                let (reply_sender, reply_receiver) = mpsc::channel();
+                if requests.send((command, reply_sender)).is_err() {
+                    return;
+                }
+                ctx.request_repaint();
+                reply_receiver
+                    .recv_timeout(COMMAND_REPLY_TIMEOUT)
+                    .unwrap_or_else(|_| "ERR no response from the UI".to_string())
+            }
+            Err(err) => format!("ERR {}", err),
+        };
+        
+        if writeln!(writer, "{}", reply).is_err() {
+            return;
+        }
+    }
+}
+
+// Single Instance
+// 在固定的本机端口上监听作为实例锁，进程退出时系统自动释放，不会残留锁文件
+const INSTANCE_LOCK_PORT: u16 = 48619;
+
+// 持有实例锁的进程对连接回复这一行，用来区分端口是否被其他程序占用
+const INSTANCE_LOCK_GREETING: &str = "SeriousClick";
+const INSTANCE_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+enum InstanceLock {
+    Acquired(TcpListener),
+    AlreadyRunning,
+    PortInUse, // 端口被其他程序占用，无法检查是否多开
+}
+
+fn acquire_instance_lock() -> InstanceLock {
+    let Ok(listener) = TcpListener::bind(("127.0.0.1", INSTANCE_LOCK_PORT)) else {
+        return if instance_lock_responds() { InstanceLock::AlreadyRunning } else { InstanceLock::PortInUse };
+    };
+    if let Ok(responder) = listener.try_clone() {
+        thread::spawn(move || {
+            for mut stream in responder.incoming().flatten() {
+                let _ = writeln!(stream, "{}", INSTANCE_LOCK_GREETING);
+            }
+        });
+    }
+    InstanceLock::Acquired(listener)
+}
+
+// 连接实例锁端口，确认对方是SeriousClick
+fn instance_lock_responds() -> bool {
+    let address = std::net::SocketAddr::from(([127, 0, 0, 1], INSTANCE_LOCK_PORT));
+    let Ok(stream) = TcpStream::connect_timeout(&address, INSTANCE_PROBE_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(INSTANCE_PROBE_TIMEOUT));
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).is_ok() && line.trim_end() == INSTANCE_LOCK_GREETING
+}
+
+// Start With System
+// 开机自启动时附带的参数，启动后最小化
+const MINIMIZED_FLAG: &str = "--minimized";
+
+#[cfg(windows)]
+const AUTOSTART_REGISTRY_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+#[cfg(windows)]
+fn reg_command() -> std::process::Command {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+    let mut command = std::process::Command::new("reg");
+    command.creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(windows)]
+fn autostart_enabled() -> bool {
+    reg_command()
+        .args(["query", AUTOSTART_REGISTRY_KEY, "/v", "SeriousClick"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+fn set_autostart(enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let output = if enabled {
+        let exe = std::env::current_exe()?;
+        let value = format!("\"{}\" {}", exe.display(), MINIMIZED_FLAG);
+        reg_command()
+            .args(["add", AUTOSTART_REGISTRY_KEY, "/v", "SeriousClick", "/t", "REG_SZ", "/d", &value, "/f"])
+            .output()?
+    } else {
+        reg_command()
+            .args(["delete", AUTOSTART_REGISTRY_KEY, "/v", "SeriousClick", "/f"])
+            .output()?
+    };
+    
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string().into());
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "linux")]
+fn autostart_desktop_entry() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|dirs| dirs.config_dir().join("autostart").join("seriousclick.desktop"))
+}
+
+#[cfg(target_os = "linux")]
+fn autostart_enabled() -> bool {
+    autostart_desktop_entry().is_some_and(|path| path.exists())
+}
+
+#[cfg(target_os = "linux")]
+fn set_autostart(enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let path = autostart_desktop_entry().ok_or("无法确定用户配置目录")?;
+    if enabled {
+        let exe = std::env::current_exe()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=SeriousClick\nExec=\"{}\" {}\nX-GNOME-Autostart-enabled=true\n",
+            exe.display(),
+            MINIMIZED_FLAG
+        );
+        fs::write(path, entry)?;
+    } else if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn autostart_enabled() -> bool {
+    false
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+fn set_autostart(_enabled: bool) -> Result<(), Box<dyn std::error::Error>> {
+    Err("当前平台不支持开机自启动".into())
+}
+
+// Relative Move Input
+// 格式: "dx,dy; dx,dy"，无法解析的项会被忽略
+fn parse_relative_moves(input: &str) -> Vec<(i32, i32)> {
+    input.split(';')
+        .filter_map(|part| {
+            let (dx, dy) = part.split_once(',')?;
+            Some((dx.trim().parse().ok()?, dy.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn format_relative_moves(moves: &[(i32, i32)]) -> String {
+    moves.iter()
+        .map(|(dx, dy)| format!("{},{}", dx, dy))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+// Pattern Input
+// 格式: "100,200,300"，无法解析的项会被忽略
+fn parse_pattern_intervals(input: &str) -> Vec<u64> {
+    input.split(',')
+        .filter_map(|part| part.trim().parse().ok())
+        .collect()
+}
+
+fn format_pattern_intervals(intervals: &[u64]) -> String {
+    intervals.iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Pattern Timeline
+// 编辑窗口中按比例绘制间隔序列，每段宽度与间隔成正比
+// 拖动两段之间的分界线在相邻两段之间转移时间，总时长不变，按PATTERN_SNAP_MS对齐
+const PATTERN_TIMELINE_HEIGHT: f32 = 28.0;
+const PATTERN_TIMELINE_MAX_WIDTH: f32 = 400.0;
+const PATTERN_BOUNDARY_GRAB_PX: f32 = 6.0;
+const PATTERN_SNAP_MS: u64 = 10;
+
+// 返回true表示拖动修改了intervals
+fn pattern_timeline(ui: &mut egui::Ui, intervals: &mut [u64]) -> bool {
+    let (rect, response) = ui.allocate_exact_size(
+        Vec2::new(ui.available_width().min(PATTERN_TIMELINE_MAX_WIDTH), PATTERN_TIMELINE_HEIGHT),
+        egui::Sense::drag(),
+    );
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, Color32::GRAY));
+    
+    let total: u64 = intervals.iter().sum();
+    if total == 0 {
+        return false;
+    }
+    
+    let to_x = |ms: u64| rect.left() + rect.width() * ms as f32 / total as f32;
+    let ends: Vec<u64> = intervals.iter()
+        .scan(0, |end, &interval| {
+            *end += interval;
+            Some(*end)
+        })
+        .collect();
+    // 可拖动的分界线：除最后一段的终点之外每段的终点
+    let boundaries = &ends[..ends.len().saturating_sub(1)];
+    let nearest_boundary = |x: f32| {
+        boundaries.iter()
+            .enumerate()
+            .map(|(i, &end)| (i, (to_x(end) - x).abs()))
+            .filter(|&(_, distance)| distance <= PATTERN_BOUNDARY_GRAB_PX)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    };
+    
+    // 正在拖动的分界线跨帧保存在egui的临时数据里
+    let drag_id = response.id.with("pattern_boundary");
+    if response.drag_started() {
+        let grabbed = response.interact_pointer_pos().and_then(|pos| nearest_boundary(pos.x));
+        ui.ctx().data_mut(|data| data.insert_temp(drag_id, grabbed));
+    }
+    let dragging = if response.dragged() {
+        ui.ctx().data(|data| data.get_temp::<Option<usize>>(drag_id)).flatten()
+    } else {
+        None
+    };
+    
+    let hovered = response.hover_pos().and_then(|pos| nearest_boundary(pos.x));
+    if dragging.is_some() || hovered.is_some() {
+        ui.ctx().output_mut(|output| output.cursor_icon = egui::CursorIcon::ResizeHorizontal);
+    }
+    
+    let mut changed = false;
+    if let (Some(i), Some(pos)) = (dragging, response.interact_pointer_pos()) {
+        let pair = intervals[i] + intervals[i + 1];
+        if pair >= 2 * PATTERN_SNAP_MS {
+            let start = ends[i] - intervals[i];
+            let pointer_ms = ((pos.x - rect.left()) / rect.width()).clamp(0.0, 1.0) * total as f32;
+            let first = pointer_ms - start as f32;
+            let first = ((first / PATTERN_SNAP_MS as f32).round().max(0.0) as u64 * PATTERN_SNAP_MS)
+                .clamp(PATTERN_SNAP_MS, pair - PATTERN_SNAP_MS);
+            if first != intervals[i] {
+                intervals[i] = first;
+                intervals[i + 1] = pair - first;
+                changed = true;
+            }
+        }
+    }
+    
+    let colors = [Color32::from_rgb(60, 100, 150), Color32::from_rgb(60, 140, 110)];
+    let mut start = 0;
+    for (i, &interval) in intervals.iter().enumerate() {
+        let segment = egui::Rect::from_min_max(egui::pos2(to_x(start), rect.top()), egui::pos2(to_x(start + interval), rect.bottom()));
+        painter.rect_filled(segment.shrink(0.5), 0.0, colors[i % colors.len()]);
+        
+        // 段太窄放不下文字时不显示数值
+        let galley = painter.layout_no_wrap(interval.to_string(), egui::FontId::proportional(12.0), Color32::WHITE);
+        if galley.size().x + 4.0 < segment.width() {
+            painter.galley(segment.center() - galley.size() / 2.0, galley);
+        }
+        
+        start += interval;
+        if i + 1 < intervals.len() {
+            let active = dragging == Some(i) || (dragging.is_none() && hovered == Some(i));
+            let stroke = egui::Stroke::new(if active { 3.0 } else { 1.0 }, Color32::WHITE);
+            painter.line_segment([egui::pos2(to_x(start), rect.top()), egui::pos2(to_x(start), rect.bottom())], stroke);
+        }
+    }
+    
+    changed
+}
+
+// Foreign Config Import
+// 其他连点器导出的简单格式，自动识别：
+// - INI键值格式（OP Auto Clicker等）：Hours/Minutes/Seconds/Milliseconds 或 Interval，MouseButton，可用[节]分成多个配置
+// - CSV格式：名称,间隔毫秒,按键，可带表头
+fn import_foreign(path: &std::path::Path) -> Result<Vec<ClickerConfig>, String> {
+    let text = fs::read_to_string(path).map_err(|err| format!("无法读取文件: {}", err))?;
+    let default_name = path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "导入的配置".to_string());
+    
+    let lines: Vec<&str> = text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+        .collect();
+    
+    let configs = if lines.iter().any(|line| line.contains('=')) {
+        parse_key_value_configs(&lines, &default_name)
+    } else if lines.iter().any(|line| line.contains(',')) {
+        parse_csv_configs(&lines)
+    } else {
+        Vec::new()
+    };
+    
+    if configs.is_empty() {
+        Err("无法识别的配置格式，支持INI键值格式和CSV（名称,间隔毫秒,按键）".to_string())
+    } else {
+        Ok(configs)
+    }
+}
+
+fn parse_foreign_button(value: &str) -> Option<MouseButtonType> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "left" | "l" | "0" | "左键" => Some(MouseButtonType::Left),
+        "right" | "r" | "1" | "右键" => Some(MouseButtonType::Right),
+        "middle" | "m" | "2" | "中键" => Some(MouseButtonType::Middle),
+        _ => None,
+    }
+}
+
+fn parse_key_value_configs(lines: &[&str], default_name: &str) -> Vec<ClickerConfig> {
+    let mut configs = Vec::new();
+    let mut current: Option<(ClickerConfig, u64, bool)> = None; // 配置、累计间隔、是否读到间隔
+    
+    // 只保留读到间隔的配置
+    fn finish(entry: Option<(ClickerConfig, u64, bool)>, configs: &mut Vec<ClickerConfig>) {
+        if let Some((mut config, interval_ms, has_interval)) = entry {
+            if has_interval {
+                config.fixed_interval_ms = interval_ms.clamp(1, MAX_FIXED_INTERVAL_MS);
+                configs.push(config);
+            }
+        }
+    }
+    
+    for line in lines {
+        if let Some(section) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            finish(current.take(), &mut configs);
+            let config = ClickerConfig { name: section.trim().to_string(), ..ClickerConfig::default() };
+            current = Some((config, 0, false));
+            continue;
+        }
+        
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let (config, interval_ms, has_interval) = current.get_or_insert_with(|| {
+            (ClickerConfig { name: default_name.to_string(), ..ClickerConfig::default() }, 0, false)
+        });
+        let value = value.trim().trim_matches('"');
+        let number = value.parse::<u64>().ok();
+        // 文件来自外部，数值可能大到溢出，饱和相加后由finish限制到MAX_FIXED_INTERVAL_MS
+        let mut add_interval = |unit_ms: u64| {
+            if let Some(n) = number {
+                *interval_ms = interval_ms.saturating_add(n.saturating_mul(unit_ms));
+                *has_interval = true;
+            }
+        };
+        
+        match key.trim().to_ascii_lowercase().as_str() {
+            "name" => config.name = value.to_string(),
+            "hours" => add_interval(3_600_000),
+            "minutes" | "mins" => add_interval(60_000),
+            "seconds" | "secs" => add_interval(1000),
+            "milliseconds" | "ms" | "interval" | "interval_ms" | "delay" => add_interval(1),
+            "mousebutton" | "button" => if let Some(button) = parse_foreign_button(value) { config.mouse_button = button; },
+            _ => {}
+        }
+    }
+    finish(current.take(), &mut configs);
+    
+    configs
+}
+
+fn parse_csv_configs(lines: &[&str]) -> Vec<ClickerConfig> {
+    lines.iter()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split(',').map(|field| field.trim().trim_matches('"')).collect();
+            // 间隔不是数字的行当作表头跳过
+            let interval_ms = fields.get(1)?.parse::<u64>().ok()?;
+            let mut config = ClickerConfig {
+                name: fields[0].to_string(),
+                fixed_interval_ms: interval_ms.clamp(1, MAX_FIXED_INTERVAL_MS),
+                ..ClickerConfig::default()
+            };
+            if let Some(button) = fields.get(2).and_then(|field| parse_foreign_button(field)) {
+                config.mouse_button = button;
+            }
+            Some(config)
+        })
+        .collect()
+}
+
+// Application State
+struct SeriousClickerApp {
+    ctx: egui::Context,
+    state: Arc<Mutex<ClickerState>>,
+    stats: ClickerStats,
+    stats_sender: mpsc::Sender<ClickerStats>,
+    stats_receiver: mpsc::Receiver<ClickerStats>,
+    clicker_thread: Option<thread::JoinHandle<()>>,
+    configs: Vec<ClickerConfig>,
+    selected_config_index: usize,
+    editing_config: ClickerConfig,
+    editing_index: Option<usize>, // 正在编辑的配置下标，None表示新建
+    is_editing: bool,
+    trial_applied: bool,          // 编辑中的配置已应用但尚未保存
+    pattern_input: String,
+    relative_moves_input: String,
+    status_message: String,
     hotkey_active: bool,
+    hotkey_thread: Option<thread::JoinHandle<()>>,
+    hotkey_registration_pending: bool, // 等待监听线程报告注册结果
+    capturing_hotkey: bool, // 正在等待用户按下新的热键
+    capturing_press_key: bool, // 编辑对话框正在等待按键动作的按键
+    last_stop_reason: Option<StopReason>, // 最近一次运行结束的原因
+    awaiting_unlock: bool, // 因锁屏停止，解锁后自动恢复
+    current_session: Option<SessionRecord>, // 正在进行的运行，停止时补全时长和点击数后写入历史
+    usage_history: Vec<SessionRecord>,
+    show_usage_dashboard: bool,
+    diagnostics: Option<String>, // 诊断窗口打开时的报告，探测配置目录会写文件，只在打开和刷新时生成
+    last_self_test: Option<Result<(), String>>, // 最近一次输入自检的结果，显示在诊断窗口中
+    pending_capture: Option<(CaptureTarget, Instant)>,
+    path_recording: Option<(Instant, thread::JoinHandle<Vec<PathPoint>>)>, // 录制开始时间和录制线程
+    path_record_secs: u64,
+    pending_start: bool, // 等待用户确认开始
+    pending_delete: bool, // 等待用户确认删除选中的配置
+    selected_rows: HashSet<usize>, // 表格中勾选的配置，用于批量操作
+    pending_bulk_delete: bool,
+    pending_portable_import: bool, // 便携模式首次运行，询问是否导入系统配置目录中的配置
+    quick_click_target: u64, // 主界面"点击 N 次"的次数
+    start_minimized: bool, // 首帧最小化窗口（--minimized）
+    autostart_enabled: bool,
+    settings: AppSettings,
+    configs_modified: Option<SystemTime>, // 最近一次加载或保存时配置文件的修改时间
+    last_config_check: Instant,
+    status_history: VecDeque<(Instant, String)>, // 最近的状态消息，最新的在最后
+    remote_sender: mpsc::Sender<RemoteRequest>,
+    remote_receiver: mpsc::Receiver<RemoteRequest>,
+    command_server: Option<(Arc<AtomicBool>, thread::JoinHandle<()>)>, // 命令端口的运行标志和监听线程
 }
 
 impl SeriousClickerApp {
-    fn new() -> Self {
+    fn new(ctx: egui::Context, start_minimized: bool) -> Self {
         let configs = Self::load_configs().unwrap_or_else(|_| vec![ClickerConfig::default()]);
-        let default_config = configs.first().cloned().unwrap_or_default();
+        let settings = AppSettings::load();
+        // 恢复上次选中的配置，配置变少了时退回到最后一个
+        let selected_config_index = settings.last_config_index.min(configs.len().saturating_sub(1));
+        let default_config = configs.get(selected_config_index).cloned().unwrap_or_default();
         let state = ClickerState::from(&default_config);
+        let (stats_sender, stats_receiver) = mpsc::channel();
+        CPS_DECIMALS.store(settings.cps_decimals, Ordering::Relaxed);
+        EMERGENCY_EXIT_ENABLED.store(settings.emergency_exit_enabled, Ordering::Relaxed);
+        
+        let pattern_input = default_config.pattern_intervals
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        
+        let relative_moves_input = format_relative_moves(&default_config.relative_moves);
+        let (remote_sender, remote_receiver) = mpsc::channel();
+        
+        let mut app = Self {
+            ctx,
+            stats: state.stats(),
+            state: Arc::new(Mutex::new(state)),
+            stats_sender,
+            stats_receiver,
+            clicker_thread: None,
+            configs,
+            selected_config_index,
+            editing_config: default_config,
+            editing_index: None,
+            is_editing: false,
+            trial_applied: false,
+            pattern_input,
+            relative_moves_input,
+            status_message: "准备就绪".to_string(),
+            hotkey_active: false,
+            hotkey_thread: None,
+            hotkey_registration_pending: false,
+            capturing_hotkey: false,
+            capturing_press_key: false,
+            last_stop_reason: None,
+            awaiting_unlock: false,
+            current_session: None,
+            usage_history: load_usage_history(),
+            show_usage_dashboard: false,
+            diagnostics: None,
+            last_self_test: None,
+            pending_capture: None,
+            path_recording: None,
+            path_record_secs: DEFAULT_PATH_RECORD_SECS,
+            pending_start: false,
+            pending_delete: false,
+            selected_rows: HashSet::new(),
+            pending_bulk_delete: false,
+            pending_portable_import: false,
+            quick_click_target: 20,
+            start_minimized,
+            autostart_enabled: autostart_enabled(),
+            settings,
+            configs_modified: config_file_modified(),
+            last_config_check: Instant::now(),
+            status_history: VecDeque::new(),
+            remote_sender,
+            remote_receiver,
+            command_server: None,
+        };
+        
+        if let Some(preferred) = &CONFIG_LOCATION.fallback_from {
+            app.set_status(format!("配置目录 {} 不可写，配置保存在: {}", preferred.display(), CONFIG_LOCATION.dir.display()));
+        }
+        
+        // 便携目录里还没有配置，而系统配置目录里有
+        if CONFIG_LOCATION.portable
+            && !get_config_dir().exists()
+            && standard_config_dir().join("configs.json").exists()
+        {
+            app.pending_portable_import = true;
+        }
+        
+        if app.settings.hotkeys_enabled {
+            if let Err(err) = app.setup_hotkey() {
+                app.set_status(format!("设置热键失败: {}", err));
+            }
+        }
+        
+        if app.settings.command_server_enabled {
+            app.start_command_server();
+        }
+        
+        if app.settings.self_test_on_startup {
+            app.self_test();
+        }
+        
+        app
+    }
+    
+    // 所有状态消息都经过这里：状态栏只显示最新一条，之前的保留在历史里
+    fn set_status(&mut self, message: impl Into<String>) {
+        self.status_message = message.into();
+        self.status_history.push_back((Instant::now(), self.status_message.clone()));
+        while self.status_history.len() > STATUS_HISTORY_LEN {
+            self.status_history.pop_front();
+        }
+    }
+    
+    fn save_settings(&mut self) {
+        if let Err(err) = self.settings.save() {
+            self.set_status(format!("保存设置失败: {}", err));
+        }
+    }
+    
+    // 将对话框中的文本输入解析进editing_config
+    fn read_editing_inputs(&mut self) {
+        // 处理模式点击间隔
+        if self.editing_config.click_mode == ClickMode::Pattern {
+            let intervals = parse_pattern_intervals(&self.pattern_input);
+            if !intervals.is_empty() {
+                self.editing_config.pattern_intervals = intervals;
+            }
+        }
+        
+        // 随机间隔的滑块互不限制，最小值大于最大值时互换
+        if self.editing_config.min_random_interval_ms > self.editing_config.max_random_interval_ms {
+            std::mem::swap(&mut self.editing_config.min_random_interval_ms, &mut self.editing_config.max_random_interval_ms);
+        }
+        
+        // 处理相对移动序列，留空表示不移动
+        self.editing_config.relative_moves = parse_relative_moves(&self.relative_moves_input);
+    }
+    
+    // 试用：只应用到运行状态，不修改配置列表也不写入文件
+    fn apply_editing_config(&mut self) {
+        self.read_editing_inputs();
+        self.apply_config(self.editing_config.clone());
+        self.trial_applied = true;
+        self.set_status(format!("已应用配置（未保存）: {}", self.editing_config.name));
+    }
+    
+    fn save_editing_config(&mut self) {
+        self.read_editing_inputs();
+        
+        // 保存配置，新建的配置追加到末尾
+        match self.editing_index {
+            Some(index) if index < self.configs.len() => {
+                self.configs[index] = self.editing_config.clone();
+                self.selected_config_index = index;
+            }
+            _ => {
+                self.configs.push(self.editing_config.clone());
+                self.selected_config_index = self.configs.len() - 1;
+            }
+        }
+        
+        // 应用配置
+        self.apply_config(self.editing_config.clone());
+        
+        // 保存到文件
+        if let Err(err) = self.save_configs() {
+            self.set_status(format!("保存配置失败: {}", err));
+        } else {
+            self.set_status(format!("配置已保存: {}", self.editing_config.name));
+        }
+        
+        self.trial_applied = false;
+        self.is_editing = false;
+    }
+    
+    // 放弃编辑，已试用的修改恢复为选中配置
+    fn cancel_editing(&mut self) {
+        if self.trial_applied {
+            if let Some(config) = self.configs.get(self.selected_config_index).cloned() {
+                self.apply_config(config);
+            }
+            self.trial_applied = false;
+        }
+        self.is_editing = false;
+    }
+    
+    // 恢复默认模板值，只保留名称
+    fn reset_editing_config(&mut self) {
+        let name = std::mem::take(&mut self.editing_config.name);
+        self.editing_config = ClickerConfig {
+            name,
+            ..ClickerConfig::default()
+        };
+        self.pattern_input = self.editing_config.pattern_intervals
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        self.relative_moves_input = format_relative_moves(&self.editing_config.relative_moves);
+    }
+    
+    // 把系统配置目录中的配置和设置复制到便携目录
+    fn import_standard_configs(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let standard_dir = standard_config_dir();
+        let config_str = fs::read_to_string(standard_dir.join("configs.json"))?;
+        let configs: Vec<ClickerConfig> = serde_json::from_str(&config_str)?;
+        
+        let settings_path = standard_dir.join("settings.json");
+        if settings_path.exists() {
+            fs::copy(settings_path, get_settings_path())?;
+            self.settings = AppSettings::load();
+            CPS_DECIMALS.store(self.settings.cps_decimals, Ordering::Relaxed);
+            EMERGENCY_EXIT_ENABLED.store(self.settings.emergency_exit_enabled, Ordering::Relaxed);
+        }
+        
+        if !configs.is_empty() {
+            self.configs = configs;
+            self.selected_config_index = 0;
+            self.selected_rows.clear();
+            self.apply_config(self.configs[0].clone());
+        }
+        self.save_configs()
+    }
+    
+    fn delete_selected_config(&mut self) {
+        if self.configs.is_empty() {
+            return;
+        }
         
-        let pattern_input = default_config.pattern_intervals
-            .iter()
-            .map(|i| i.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
+        let removed = self.configs.remove(self.selected_config_index);
+        self.selected_rows.clear();
+        if self.configs.is_empty() {
+            self.configs.push(ClickerConfig::default());
+        }
+        self.selected_config_index = self.selected_config_index.min(self.configs.len() - 1);
+        self.apply_config(self.configs[self.selected_config_index].clone());
+        let _ = self.save_configs();
+        self.set_status(format!("已删除配置: {}", removed.name));
+    }
+    
+    // 删除表格中勾选的所有配置，尽量保持当前选中的配置不变
+    fn delete_selected_rows(&mut self) {
+        let rows = std::mem::take(&mut self.selected_rows);
+        if rows.is_empty() {
+            return;
+        }
         
-        Self {
-            state: Arc::new(Mutex::new(state)),
-            clicker_thread: None,
-            configs,
-            selected_config_index: 0,
-            editing_config: default_config,
-            is_editing: false,
-            pattern_input,
-            status_message: "准备就绪".to_string(),
-            hotkey_active: false,
+        let removed_before_selection = rows.iter().filter(|&&i| i < self.selected_config_index).count();
+        let mut index = 0;
+        self.configs.retain(|_| {
+            let keep = !rows.contains(&index);
+            index += 1;
+            keep
+        });
+        
+        if self.configs.is_empty() {
+            self.configs.push(ClickerConfig::default());
+        }
+        self.selected_config_index = (self.selected_config_index - removed_before_selection).min(self.configs.len() - 1);
+        self.apply_config(self.configs[self.selected_config_index].clone());
+        let _ = self.save_configs();
+        self.set_status(format!("已删除 {} 个配置", rows.len()));
+    }
+    
+    fn import_foreign_configs(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("Config", &["ini", "cfg", "txt", "csv"])
+            .pick_file()
+        else {
+            return;
+        };
+        
+        match import_foreign(&path) {
+            Ok(configs) => {
+                let count = configs.len();
+                self.configs.extend(configs);
+                match self.save_configs() {
+                    Ok(()) => self.set_status(format!("已导入 {} 个配置", count)),
+                    Err(err) => self.set_status(format!("已导入 {} 个配置，但保存失败: {}", count, err)),
+                }
+            }
+            Err(err) => self.set_status(format!("导入失败: {}", err)),
+        }
+    }
+    
+    // 导入其他人导出的JSON配置，追加到列表末尾，重名的加上序号
+    fn import_configs(&mut self) {
+        let Some(path) = FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        
+        let imported: Vec<ClickerConfig> = match fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|config_str| serde_json::from_str(&config_str).map_err(|err| err.to_string()))
+        {
+            Ok(configs) => configs,
+            Err(err) => {
+                self.set_status(format!("导入失败，不是有效的配置文件: {}", err));
+                return;
+            }
+        };
+        
+        let count = imported.len();
+        for mut config in imported {
+            config.name = self.unique_config_name(&config.name);
+            self.configs.push(config);
+        }
+        match self.save_configs() {
+            Ok(()) => self.set_status(format!("已导入 {} 个配置", count)),
+            Err(err) => self.set_status(format!("已导入 {} 个配置，但保存失败: {}", count, err)),
+        }
+    }
+    
+    // 名称已被占用时依次尝试 "名称 (2)"、"名称 (3)"...
+    fn unique_config_name(&self, name: &str) -> String {
+        let taken = |candidate: &str| self.configs.iter().any(|config| config.name == candidate);
+        if !taken(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} ({})", name, n))
+            .find(|candidate| !taken(candidate))
+            .unwrap_or_else(|| name.to_string())
+    }
+    
+    // 将指定配置导出到用户选择的JSON文件
+    fn export_configs(&mut self, indices: &[usize]) {
+        let configs: Vec<ClickerConfig> = indices.iter()
+            .filter_map(|&i| self.configs.get(i).cloned())
+            .collect();
+        
+        let Some(path) = FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("configs.json")
+            .save_file()
+        else {
+            return;
+        };
+        
+        let result = serde_json::to_string_pretty(&configs)
+            .map_err(|err| err.to_string())
+            .and_then(|config_str| fs::write(&path, config_str).map_err(|err| err.to_string()));
+        self.set_status(match result {
+            Ok(()) => format!("已导出 {} 个配置到 {}", configs.len(), path.display()),
+            Err(err) => format!("导出配置失败: {}", err),
+        });
+    }
+    
+    // 按排序设置得到的显示顺序，不改变self.configs本身的顺序
+    fn sorted_config_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.configs.len()).collect();
+        let Some(column) = self.settings.sort_column else {
+            return indices;
+        };
+        
+        indices.sort_by(|&a, &b| {
+            let (a, b) = (&self.configs[a], &self.configs[b]);
+            match column {
+                GridColumn::Number => std::cmp::Ordering::Equal,
+                GridColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                GridColumn::ClickType => a.click_mode.name().cmp(b.click_mode.name()),
+                GridColumn::Interval => a.nominal_interval_ms().total_cmp(&b.nominal_interval_ms()),
+                // 有上限的排在前面，一直运行的排在最后
+                GridColumn::RunMode => (a.max_clicks.unwrap_or(u64::MAX), a.max_duration_secs.unwrap_or(u64::MAX))
+                    .cmp(&(b.max_clicks.unwrap_or(u64::MAX), b.max_duration_secs.unwrap_or(u64::MAX))),
+                GridColumn::LastUsed => a.last_used.cmp(&b.last_used),
+            }
+        });
+        if self.settings.sort_descending {
+            indices.reverse();
+        }
+        indices
+    }
+    
+    // 点击表头：同一列切换升降序，新列从升序开始
+    fn toggle_sort(&mut self, column: GridColumn) {
+        if self.settings.sort_column == Some(column) {
+            self.settings.sort_descending = !self.settings.sort_descending;
+        } else {
+            // 最近使用默认把最新的排在前面
+            self.settings.sort_column = Some(column);
+            self.settings.sort_descending = column == GridColumn::LastUsed;
+        }
+        self.save_settings();
+    }
+    
+    // 记录选中配置的使用时间并保存；试用中的配置只记在编辑副本上，保存时一起写入
+    fn touch_selected_config(&mut self) {
+        if self.trial_applied {
+            self.editing_config.last_used = Some(unix_timestamp());
+            return;
+        }
+        if let Some(config) = self.configs.get_mut(self.selected_config_index) {
+            config.last_used = Some(unix_timestamp());
+            let _ = self.save_configs();
+        }
+    }
+    
+    fn self_test(&mut self) {
+        let result = run_input_self_test();
+        self.set_status(match &result {
+            Ok(()) => "输入自检通过: 鼠标输入注入正常".to_string(),
+            Err(err) => format!("输入自检失败: {}（可能需要管理员权限或辅助功能授权）", err),
+        });
+        self.last_self_test = Some(result);
+    }
+    
+    // 诊断窗口的纯文本报告，方便直接粘贴到问题反馈中
+    fn diagnostics_report(&self) -> String {
+        let config_dir = &CONFIG_LOCATION.dir;
+        let mut lines = vec![
+            format!("SeriousClick {}", env!("CARGO_PKG_VERSION")),
+            format!("系统: {} ({})", std::env::consts::OS, std::env::consts::ARCH),
+            format!("显示服务器: {}", display_server()),
+            format!("输入注入: {}", if display_available() { "可用 (enigo)" } else { "不可用，没有显示器" }),
+            format!("输入自检: {}", match &self.last_self_test {
+                Some(Ok(())) => "通过".to_string(),
+                Some(Err(err)) => format!("失败: {}", err),
+                None => "未运行".to_string(),
+            }),
+            format!("锁屏检测: {}", match session_locked() {
+                Some(_) => "支持",
+                None => "不支持",
+            }),
+            format!("配置目录: {}{}", config_dir.display(), if CONFIG_LOCATION.portable { "（便携模式）" } else { "" }),
+            format!("配置目录可写: {}", if dir_is_writable(config_dir) { "是" } else { "否" }),
+        ];
+        if let Some(preferred) = &CONFIG_LOCATION.fallback_from {
+            lines.push(format!("首选目录不可写: {}", preferred.display()));
+        }
+        let pixels_per_point = self.ctx.pixels_per_point();
+        lines.push(format!("界面缩放: {}（点击坐标为物理像素，不受界面缩放影响）", pixels_per_point));
+        if let Some(pos) = self.ctx.input(|i| i.pointer.hover_pos()) {
+            let (x, y) = logical_to_physical(pos, pixels_per_point);
+            lines.push(format!("鼠标在窗口内: 界面坐标 ({:.1}, {:.1}) = 窗口内物理像素 ({}, {})", pos.x, pos.y, x, y));
         }
+        lines.extend(self.hotkey_diagnostics());
+        lines.join("\n")
     }
     
     fn load_configs() -> Result<Vec<ClickerConfig>, Box<dyn std::error::Error>> {
@@ -211,178 +3627,804 @@ impl SeriousClickerApp {
         Ok(configs)
     }
     
-    fn save_configs(&self) -> Result<(), Box<dyn std::error::Error>> {
+    fn save_configs(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         let config_path = get_config_dir();
         let config_str = serde_json::to_string_pretty(&self.configs)?;
         fs::write(config_path, config_str)?;
+        // 记录自己写入后的修改时间，避免把自己的保存当成外部修改
+        self.configs_modified = config_file_modified();
         Ok(())
     }
     
+    // 配置文件被手动编辑或云同步修改后重新加载，尽量按名称保持当前选择
+    fn reload_configs_if_changed(&mut self) {
+        if !self.settings.reload_configs_on_change || self.last_config_check.elapsed() < CONFIG_WATCH_INTERVAL {
+            return;
+        }
+        self.last_config_check = Instant::now();
+        
+        // 编辑中先不替换，关闭编辑窗口后再加载
+        if self.is_editing {
+            return;
+        }
+        
+        let modified = config_file_modified();
+        if modified.is_none() || modified == self.configs_modified {
+            return;
+        }
+        self.configs_modified = modified;
+        
+        let configs = match Self::load_configs() {
+            Ok(configs) if !configs.is_empty() => configs,
+            Ok(_) => vec![ClickerConfig::default()],
+            Err(err) => {
+                self.set_status(format!("配置文件已被修改，但无法解析: {}", err));
+                return;
+            }
+        };
+        
+        let selected_name = self.configs.get(self.selected_config_index).map(|config| config.name.clone());
+        self.configs = configs;
+        self.selected_config_index = selected_name
+            .and_then(|name| self.configs.iter().position(|config| config.name == name))
+            .unwrap_or(0);
+        self.selected_rows.clear();
+        
+        if !self.stats.is_running {
+            let config = self.configs[self.selected_config_index].clone();
+            self.apply_config(config);
+        }
+        
+        self.set_status("配置文件已在外部修改，已重新加载");
+    }
+    
     fn apply_config(&mut self, config: ClickerConfig) {
-        let mut state = self.state.lock().unwrap();
+        let mut state = lock_state(&self.state);
         state.click_mode = config.click_mode;
         state.mouse_button = config.mouse_button;
+        state.combo_buttons = config.combo_buttons.clone();
+        state.action_type = config.action_type;
         state.fixed_interval_ms = config.fixed_interval_ms;
-        state.min_random_interval_ms = config.min_random_interval_ms;
-        state.max_random_interval_ms = config.max_random_interval_ms;
+        state.rate_cps = config.rate_cps;
+        (state.min_random_interval_ms, state.max_random_interval_ms) =
+            random_interval_bounds(config.min_random_interval_ms, config.max_random_interval_ms);
         state.pattern_intervals = config.pattern_intervals.clone();
+        state.randomize_start_index = config.randomize_start_index;
+        state.ramp_start_ms = config.ramp_start_ms;
+        state.ramp_end_ms = config.ramp_end_ms;
+        state.ramp_duration_ms = config.ramp_duration_ms;
+        state.burst_size = config.burst_size;
+        state.burst_interval_ms = config.burst_interval_ms;
+        state.burst_pause_ms = config.burst_pause_ms;
+        state.target_cps = config.target_cps;
+        state.relative_moves = config.relative_moves.clone();
+        state.cursor_offset = config.cursor_offset;
+        state.target_position = config.target_position;
+        state.position_jitter_px = config.position_jitter_px;
+        state.clicks_per_action = config.clicks_per_action;
+        state.multi_click_gap_ms = config.multi_click_gap_ms;
+        state.post_click_delay_ms = config.post_click_delay_ms;
+        state.path = config.path.clone();
+        state.click_points = config.click_points.clone();
+        state.press_key = config.press_key.clone();
+        state.key_repeat = config.key_repeat;
+        state.key_repeat_delay_ms = config.key_repeat_delay_ms;
+        state.key_repeat_interval_ms = config.key_repeat_interval_ms;
+        state.stuck_timeout_secs = config.stuck_timeout_secs;
+        state.target_window_title = config.target_window_title.clone();
+        state.stop_on_window_lost = config.stop_on_window_lost;
+        state.restrict_to_window_client = config.restrict_to_window_client;
+        state.max_cps = config.max_cps;
+        state.pixel_condition = config.pixel_condition;
+        state.loop_count = config.loop_count;
+        state.loop_gap_ms = config.loop_gap_ms;
         
         self.pattern_input = config.pattern_intervals
             .iter()
             .map(|i| i.to_string())
             .collect::<Vec<_>>()
             .join(",");
+        self.relative_moves_input = format_relative_moves(&config.relative_moves);
     }
 
-    fn toggle_clicker(&mut self) {
+    // 热键处理后的可选反馈，方便确认后台收到了热键
+    fn hotkey_feedback(&self) {
+        if self.settings.hotkey_feedback_sound {
+            play_alert_sound();
+        }
+        if self.settings.hotkey_feedback_notification {
+            let action = if self.pending_start {
+                "等待确认开始"
+            } else if lock_state(&self.state).is_running {
+                "已开始"
+            } else {
+                "已停止"
+            };
+            show_desktop_notification(format!("热键: {}", action));
+        }
+    }
+    
+    // reason为正在运行时停止的原因
+    fn toggle_clicker(&mut self, reason: StopReason) {
         let is_running = {
-            let state = self.state.lock().unwrap();
+            let state = lock_state(&self.state);
             state.is_running
         };
         
         if is_running {
-            self.stop_clicker();
+            self.stop_clicker(reason);
+        } else if self.configs.get(self.selected_config_index).is_some_and(|config| config.confirm_before_start) {
+            // 高风险配置需要先确认
+            self.pending_start = true;
         } else {
             self.start_clicker();
         }
     }
     
+    // 编辑窗口或任何确认框正在显示
+    fn modal_open(&self) -> bool {
+        self.is_editing || self.pending_start || self.pending_delete || self.pending_bulk_delete || self.pending_portable_import || self.capturing_hotkey
+    }
+    
+    // 滚轮实时调速：只改运行中的状态，不写回配置
+    fn adjust_live_rate(&mut self, faster: bool, fine: bool) {
+        let step = if fine { SCROLL_INTERVAL_FINE_STEP_MS } else { SCROLL_INTERVAL_STEP_MS };
+        let mut state = lock_state(&self.state);
+        let max_cps = effective_max_cps(state.max_cps, state.app_max_cps);
+        // 间隔不低于CPS上限对应的最小值
+        let min_interval_ms = ((1000.0 / max_cps).ceil() as u64).max(1);
+        let adjust_interval = |interval: u64| {
+            let interval = if faster { interval.saturating_sub(step) } else { interval.saturating_add(step) };
+            interval.clamp(min_interval_ms, MAX_FIXED_INTERVAL_MS)
+        };
+        let message = match state.click_mode {
+            ClickMode::FixedInterval => match state.rate_cps {
+                Some(cps) => {
+                    // 频率表示时按CPS调整，步长换算为 1 / 0.1 CPS
+                    let cps_step = step as f64 / SCROLL_INTERVAL_STEP_MS as f64;
+                    let cps = if faster { cps + cps_step } else { cps - cps_step };
+                    let cps = cps.clamp(0.1, max_cps);
+                    state.rate_cps = Some(cps);
+                    format!("频率已调整为 {}", format_cps(cps))
+                }
+                None => {
+                    state.fixed_interval_ms = adjust_interval(state.fixed_interval_ms);
+                    format!("间隔已调整为 {}", format_interval_ms(state.fixed_interval_ms))
+                }
+            },
+            ClickMode::RandomInterval => {
+                // 整体平移随机范围，保持范围宽度不变
+                let width = state.max_random_interval_ms.saturating_sub(state.min_random_interval_ms);
+                state.min_random_interval_ms = adjust_interval(state.min_random_interval_ms).min(MAX_FIXED_INTERVAL_MS.saturating_sub(width));
+                state.max_random_interval_ms = state.min_random_interval_ms + width;
+                format!("随机间隔已调整为 {}-{} ms", state.min_random_interval_ms, state.max_random_interval_ms)
+            }
+            ClickMode::Continuous => {
+                let target = if faster { state.target_cps.saturating_add(step as u32) } else { state.target_cps.saturating_sub(step as u32) };
+                state.target_cps = target.clamp(1, max_cps as u32);
+                format!("目标速率已调整为 {}", format_cps(state.target_cps as f64))
+            }
+            ClickMode::Pattern => "模式点击不支持滚轮调速".to_string(),
+            ClickMode::Ramp => {
+                // 调整最终间隔，加速过程的起点不变
+                state.ramp_end_ms = adjust_interval(state.ramp_end_ms);
+                format!("渐快模式的最终间隔已调整为 {}", format_interval_ms(state.ramp_end_ms))
+            }
+            ClickMode::Burst => {
+                // 调整组间停顿，组内节奏不变
+                state.burst_pause_ms = adjust_interval(state.burst_pause_ms);
+                format!("连发停顿已调整为 {}", format_interval_ms(state.burst_pause_ms))
+            }
+        };
+        drop(state);
+        self.set_status(message);
+    }
+    
+    // 当前生效的配置：试用未保存时是编辑中的配置，否则是选中的配置
+    fn active_config(&self) -> Option<&ClickerConfig> {
+        if self.trial_applied {
+            Some(&self.editing_config)
+        } else {
+            self.configs.get(self.selected_config_index)
+        }
+    }
+    
     fn start_clicker(&mut self) {
-        let mut state = self.state.lock().unwrap();
-        if state.is_running {
+        self.start_run(None);
+    }
+    
+    // 以指定点击上限启动，None表示使用配置的上限，配置也没有时一直运行到手动停止
+    fn start_run(&mut self, click_limit: Option<u64>) {
+        if lock_state(&self.state).is_running {
             return; // 已经在运行了
         }
         
+        let click_limit = click_limit.or_else(|| self.active_config().and_then(|config| config.max_clicks));
+        
+        // 启动前检查当前配置，有错误时不启动
+        let issues = self.active_config().map(|config| config.validate()).unwrap_or_default();
+        if let Some(error) = issues.iter().find(|issue| issue.is_error()) {
+            self.set_status(format!("无法启动: {}", error.message()));
+            return;
+        }
+        
+        // 先把鼠标移到起点，确认将要点击的位置
+        let start_point = self.active_config()
+            .filter(|config| config.preposition_cursor)
+            .and_then(|config| config.start_point());
+        if let Some((x, y)) = start_point {
+            MouseControllable::mouse_move_to(&mut Enigo::new(), x, y);
+        }
+        
+        let mut state = lock_state(&self.state);
         state.is_running = true;
         state.start_time = Some(Instant::now());
-        state.click_count = 0;
+        if self.settings.reset_count_on_start {
+            state.click_count = 0;
+            state.skipped_clicks = 0;
+        }
+        state.run_start_count = state.click_count;
+        state.loops_done = 0;
+        state.loop_start_count = state.click_count;
+        state.loop_start_elapsed = Duration::ZERO;
+        state.recent_clicks.clear();
+        state.paused_at = None;
+        state.click_limit = click_limit;
+        state.time_limit = self.active_config()
+            .and_then(|config| config.max_duration_secs)
+            .map(Duration::from_secs);
+        state.run_completed = false;
+        state.rate_limited = false;
+        state.app_max_cps = self.settings.max_cps;
+        state.stop_on_session_lock = self.settings.stop_on_session_lock;
+        state.high_precision_timing = self.settings.high_precision_timing;
+        state.rng_seed = self.settings.rng_seed;
+        state.stop_reason = None;
+        self.stats = state.stats();
         drop(state);
         
-        self.status_message = "连点器已启动".to_string();
+        self.awaiting_unlock = false;
+        
+        // 丢弃上一次运行残留的快照
+        while self.stats_receiver.try_recv().is_ok() {}
+        
+        self.touch_selected_config();
+        
+        if self.settings.record_usage_history {
+            self.current_session = self.active_config().map(|config| SessionRecord {
+                config_name: config.name.clone(),
+                started_at: unix_timestamp(),
+                duration_secs: 0,
+                clicks: 0,
+            });
+        }
+        
+        let warnings: Vec<&str> = issues.iter().map(|issue| issue.message()).collect();
+        if warnings.is_empty() {
+            self.set_status("连点器已启动");
+        } else {
+            self.set_status(format!("连点器已启动（警告: {}）", warnings.join("；")));
+        }
         
         // 创建点击线程
         let state_clone = Arc::clone(&self.state);
+        let stats_sender = self.stats_sender.clone();
+        let ctx = self.ctx.clone();
         self.clicker_thread = Some(thread::spawn(move || {
             let mut enigo = Enigo::new();
-            let mut pattern_index = 0;
+            run_click_loop(&mut enigo, &state_clone, |stats| {
+                let _ = stats_sender.send(stats);
+                ctx.request_repaint();
+            });
+        }));
+    }
+    
+    fn stop_clicker(&mut self, reason: StopReason) {
+        let mut state = lock_state(&self.state);
+        if !state.is_running {
+            return; // 已经停止了
+        }
+        
+        state.is_running = false;
+        state.paused_at = None;
+        drop(state);
+        
+        self.record_stop(reason);
+        
+        if let Some(handle) = self.clicker_thread.take() {
+            // 线程会自行结束，因为我们已经设置了is_running = false
+            let _ = handle.join();
+        }
+        
+        self.stats = lock_state(&self.state).stats();
+    }
+    
+    // 暂停保留点击数、运行时间和序列位置，继续时从暂停处接着点击
+    fn toggle_pause(&mut self) {
+        let mut state = lock_state(&self.state);
+        if !state.is_running {
+            return;
+        }
+        
+        let message = match state.paused_at.take() {
+            Some(paused_at) => {
+                // 把开始时间后移暂停的时长，运行时间和时长上限都不计暂停
+                let paused_for = paused_at.elapsed();
+                if let Some(start_time) = &mut state.start_time {
+                    *start_time += paused_for;
+                }
+                state.recent_clicks.clear();
+                "已继续"
+            }
+            None => {
+                state.paused_at = Some(Instant::now());
+                "已暂停，点击\"继续\"接着运行"
+            }
+        };
+        self.stats = state.stats();
+        drop(state);
+        
+        self.set_status(message);
+    }
+    
+    fn record_stop(&mut self, reason: StopReason) {
+        self.set_status(reason.status_text());
+        self.last_stop_reason = Some(reason);
+        self.finish_session();
+    }
+    
+    // 把刚结束的运行写入本地使用历史
+    fn finish_session(&mut self) {
+        let Some(mut session) = self.current_session.take() else {
+            return;
+        };
+        
+        let state = lock_state(&self.state);
+        session.clicks = state.click_count.saturating_sub(state.run_start_count);
+        drop(state);
+        session.duration_secs = (unix_timestamp() - session.started_at).max(0) as u64;
+        
+        self.usage_history.push(session);
+        if self.usage_history.len() > MAX_USAGE_RECORDS {
+            let excess = self.usage_history.len() - MAX_USAGE_RECORDS;
+            self.usage_history.drain(..excess);
+        }
+        if let Err(err) = save_usage_history(&self.usage_history) {
+            self.set_status(format!("保存使用历史失败: {}", err));
+        }
+    }
+    
+    fn setup_hotkey(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        // 如果热键已经激活，不需要再次设置
+        if self.hotkey_active {
+            return Ok(());
+        }
+        
+        // 设置热键状态
+        *HOTKEY_ACTIVE.lock().unwrap() = true;
+        
+        // 监听线程还在时只需重新打开开关，避免重复注册和遗留线程
+        if self.hotkey_thread.as_ref().is_some_and(|handle| !handle.is_finished()) {
+            self.hotkey_active = true;
+            self.set_status(format!("热键已激活: {} = 开始/停止", self.hotkey_name()));
+            return Ok(());
+        }
+        
+        // 在新线程中启动热键监听，注册结果由check_hotkey_registration报告
+        *HOTKEY_REGISTRATION.lock().unwrap() = None;
+        let ctx = self.ctx.clone();
+        let repaint_ctx = self.ctx.clone();
+        let exit_state = Arc::clone(&self.state);
+        let (modifiers, keycode) = (self.settings.hotkey_modifiers, self.settings.hotkey_keycode);
+        let generation = HOTKEY_GENERATION.load(Ordering::SeqCst);
+        self.hotkey_thread = Some(thread::spawn(move || {
+            #[cfg(windows)]
+            HOTKEY_LISTENER_THREAD_ID.store(unsafe { win32::GetCurrentThreadId() }, Ordering::SeqCst);
+            let current = move || HOTKEY_GENERATION.load(Ordering::SeqCst) == generation;
+            let mut listener = Listener::new();
             
-            loop {
-                let (should_continue, delay, button, mode, pattern) = {
-                    let state = state_clone.lock().unwrap();
-                    if !state.is_running {
-                        break;
+            // 注册开始/停止热键，根据hotkey库0.3.1版本，使用0作为modifiers表示没有修饰键
+            let registration = listener.register_hotkey(
+                modifiers,
+                keycode,
+                move || {
+                    // 总开关关闭或热键已重新绑定时忽略按键
+                    if !current() || !*HOTKEY_ACTIVE.lock().unwrap() {
+                        return;
                     }
                     
-                    let button = state.mouse_button.to_enigo_button();
-                    let mode = state.click_mode;
-                    
-                    let delay = match mode {
-                        ClickMode::FixedInterval => state.fixed_interval_ms,
-                        ClickMode::RandomInterval => {
-                            let mut rng = rand::thread_rng();
-                            rng.gen_range(state.min_random_interval_ms..=state.max_random_interval_ms)
-                        },
-                        ClickMode::Continuous => 1, // 尽可能快的点击
-                        ClickMode::Pattern => {
-                            if state.pattern_intervals.is_empty() {
-                                100 // 默认值
-                            } else {
-                                state.pattern_intervals[pattern_index]
-                            }
-                        },
-                    };
-                    
-                    let pattern = state.pattern_intervals.clone();
-                    (true, delay, button, mode, pattern)
-                };
-                
-                if should_continue {
-                    // 执行点击
-                    enigo.mouse_click(button);
-                    
-                    // 更新状态
-                    let mut state = state_clone.lock().unwrap();
-                    state.click_count += 1;
-                    state.last_click_time = Some(Instant::now());
-                    drop(state);
-                    
-                    // 更新模式索引
-                    if mode == ClickMode::Pattern && !pattern.is_empty() {
-                        pattern_index = (pattern_index + 1) % pattern.len();
+                    // 通过命令通道发送切换命令
+                    let mut command = HOTKEY_COMMAND.lock().unwrap();
+                    *command = Some(true); // 设置为Some(true)表示需要切换状态
+                    *HOTKEY_LAST_COMMAND.lock().unwrap() = Some(Instant::now());
+                    ctx.request_repaint(); // 界面不再持续重绘，需要唤醒
+                },
+            );
+            
+            // 强制退出不受热键总开关影响，只看设置中是否启用
+            let exit_registration = listener.register_hotkey(
+                EMERGENCY_EXIT_MODIFIERS,
+                EMERGENCY_EXIT_KEYCODE,
+                move || {
+                    if current() && EMERGENCY_EXIT_ENABLED.load(Ordering::Relaxed) {
+                        emergency_exit(&exit_state);
                     }
-                    
-                    // 等待下一次点击
-                    thread::sleep(Duration::from_millis(delay));
+                },
+            );
+            
+            let registered = registration.is_ok();
+            if !current() {
+                return; // 注册期间热键已被重新绑定
+            }
+            *EMERGENCY_EXIT_REGISTRATION.lock().unwrap() = exit_registration.err();
+            *HOTKEY_REGISTRATION.lock().unwrap() = Some(registration.map(|_| ()));
+            repaint_ctx.request_repaint();
+            if registered {
+                // 开始监听热键
+                listener.listen();
+            }
+        }));
+        
+        self.hotkey_active = true;
+        self.hotkey_registration_pending = true;
+        self.set_status(format!("正在注册热键 {}...", self.hotkey_name()));
+        
+        Ok(())
+    }
+    
+    fn hotkey_name(&self) -> String {
+        hotkey_name(self.settings.hotkey_modifiers, self.settings.hotkey_keycode)
+    }
+    
+    // 重新绑定开始/停止热键：先让旧监听线程退出，热键开启时再用新按键注册
+    fn rebind_hotkey(&mut self, modifiers: u32, keycode: u32) {
+        if (modifiers, keycode) == (EMERGENCY_EXIT_MODIFIERS, EMERGENCY_EXIT_KEYCODE) {
+            self.set_status(format!("{} 已用于强制退出，请选择其他按键", hotkey_name(modifiers, keycode)));
+            return;
+        }
+        
+        self.settings.hotkey_modifiers = modifiers;
+        self.settings.hotkey_keycode = keycode;
+        self.save_settings();
+        
+        HOTKEY_GENERATION.fetch_add(1, Ordering::SeqCst);
+        quit_hotkey_listener();
+        // 非Windows平台线程不会退出，不等待
+        self.hotkey_thread = None;
+        self.hotkey_registration_pending = false;
+        *HOTKEY_REGISTRATION.lock().unwrap() = None;
+        
+        if self.hotkey_active {
+            self.hotkey_active = false;
+            if let Err(err) = self.setup_hotkey() {
+                self.set_status(format!("设置热键失败: {}", err));
+            }
+        } else {
+            self.set_status(format!("热键已设置为 {}，启用热键后生效", self.hotkey_name()));
+        }
+    }
+    
+    // 注册在监听线程中完成，失败（例如按键已被其他程序占用）时提示并关闭热键
+    fn check_hotkey_registration(&mut self) {
+        if !self.hotkey_registration_pending {
+            return;
+        }
+        let registration = HOTKEY_REGISTRATION.lock().unwrap().clone();
+        let Some(registration) = registration else {
+            return;
+        };
+        
+        self.hotkey_registration_pending = false;
+        match registration {
+            Ok(()) => self.set_status(format!("热键已激活: {} = 开始/停止", self.hotkey_name())),
+            Err(err) => {
+                *HOTKEY_ACTIVE.lock().unwrap() = false;
+                self.hotkey_active = false;
+                self.set_status(format!("热键 {} 注册失败，可能已被其他程序占用: {}", self.hotkey_name(), err));
+            }
+        }
+        
+        // 强制退出热键单独注册，失败时开始/停止热键仍然可用
+        let exit_error = EMERGENCY_EXIT_REGISTRATION.lock().unwrap().take();
+        if let Some(err) = exit_error.filter(|_| self.settings.emergency_exit_enabled) {
+            self.set_status(format!("强制退出热键 Ctrl+Alt+F8 注册失败: {}", err));
+        }
+    }
+    
+    // 热键总开关，选择会保存到设置中
+    fn set_hotkeys_enabled(&mut self, enabled: bool) {
+        if enabled {
+            if let Err(err) = self.setup_hotkey() {
+                self.set_status(format!("设置热键失败: {}", err));
+            }
+        } else {
+            *HOTKEY_ACTIVE.lock().unwrap() = false;
+            self.hotkey_active = false;
+            self.set_status("热键已禁用");
+        }
+        
+        self.settings.hotkeys_enabled = self.hotkey_active;
+        self.save_settings();
+    }
+    
+    // 点击线程panic退出时不会发送最终快照，需要主动恢复状态
+    fn recover_crashed_clicker(&mut self) {
+        let finished = self.clicker_thread.as_ref().is_some_and(|handle| handle.is_finished());
+        if !finished || !self.stats.is_running {
+            return;
+        }
+        
+        let Some(handle) = self.clicker_thread.take() else {
+            return;
+        };
+        if handle.join().is_ok() {
+            // 正常退出，最终快照仍在通道中
+            return;
+        }
+        
+        let mut state = lock_state(&self.state);
+        state.is_running = false;
+        state.stop_reason = None;
+        self.stats = state.stats();
+        drop(state);
+        self.record_stop(StopReason::Panic);
+    }
+    
+    fn start_command_server(&mut self) {
+        self.stop_command_server();
+        
+        let running = Arc::new(AtomicBool::new(true));
+        match spawn_command_server(self.settings.command_port, self.ctx.clone(), self.remote_sender.clone(), Arc::clone(&running)) {
+            Ok(handle) => {
+                self.command_server = Some((running, handle));
+                self.set_status(format!("命令端口已开启: 127.0.0.1:{}", self.settings.command_port));
+            }
+            Err(err) => {
+                self.set_status(format!("无法开启命令端口 {}: {}", self.settings.command_port, err));
+            }
+        }
+    }
+    
+    fn stop_command_server(&mut self) {
+        if let Some((running, handle)) = self.command_server.take() {
+            running.store(false, Ordering::Relaxed);
+            // 等监听线程释放端口，之后可以立即在同一端口重新开启
+            let _ = handle.join();
+        }
+    }
+    
+    fn handle_remote_command(&mut self, command: RemoteCommand) -> String {
+        match command {
+            RemoteCommand::Start => {
+                if self.stats.is_running {
+                    return "OK already running".to_string();
+                }
+                self.start_clicker();
+                if self.stats.is_running { "OK started".to_string() } else { format!("ERR {}", self.status_message) }
+            }
+            RemoteCommand::Stop => {
+                self.stop_clicker(StopReason::Remote);
+                "OK stopped".to_string()
+            }
+            RemoteCommand::Toggle => {
+                if self.stats.is_running {
+                    self.handle_remote_command(RemoteCommand::Stop)
+                } else {
+                    self.handle_remote_command(RemoteCommand::Start)
                 }
             }
-        }));
+            RemoteCommand::Select(name) => {
+                let Some(index) = self.configs.iter().position(|config| config.name == name) else {
+                    return format!("ERR no config named {}", name);
+                };
+                self.selected_config_index = index;
+                let config = self.configs[index].clone();
+                self.apply_config(config);
+                self.set_status(format!("已切换到配置: {}", name));
+                format!("OK selected {}", name)
+            }
+            RemoteCommand::Status => {
+                let config_name = self.configs
+                    .get(self.selected_config_index)
+                    .map(|config| config.name.as_str())
+                    .unwrap_or("");
+                format!("OK {} | 配置: {}", self.get_status_text(), config_name)
+            }
+        }
     }
     
-    fn stop_clicker(&mut self) {
-        let mut state = self.state.lock().unwrap();
-        if !state.is_running {
-            return; // 已经停止了
+    // 热键内部状态，供排查"热键失灵"使用
+    fn hotkey_diagnostics(&self) -> Vec<String> {
+        let listener_state = match &self.hotkey_thread {
+            Some(handle) if handle.is_finished() => "已退出",
+            Some(_) => "运行中",
+            None => "未启动",
+        };
+        let registration = match &*HOTKEY_REGISTRATION.lock().unwrap() {
+            Some(Ok(())) => format!(
+                "已注册 {} (键码 0x{:02X}, 修饰键 0x{:X})",
+                self.hotkey_name(), self.settings.hotkey_keycode, self.settings.hotkey_modifiers
+            ),
+            Some(Err(err)) => format!("注册失败: {}", err),
+            None => "未注册".to_string(),
+        };
+        let last_command = match *HOTKEY_LAST_COMMAND.lock().unwrap() {
+            Some(time) => format!("{:.1}秒前", time.elapsed().as_secs_f32()),
+            None => "无".to_string(),
+        };
+        
+        vec![
+            format!("热键开关: {}", if *HOTKEY_ACTIVE.lock().unwrap() { "开启" } else { "关闭" }),
+            format!("注册状态: {}", registration),
+            format!("监听线程: {}", listener_state),
+            format!("最近命令: {}", last_command),
+            format!("待处理命令: {}", if HOTKEY_COMMAND.lock().unwrap().is_some() { "有" } else { "无" }),
+        ]
+    }
+    
+    // 当前所有已安排的定时动作，按发生时间排列，等待解锁没有确定时间放在最后
+    fn pending_actions(&self) -> Vec<PendingAction> {
+        let mut actions = Vec::new();
+        if let Some((target, deadline)) = self.pending_capture {
+            actions.push(PendingAction::Capture(target, deadline));
+        }
+        if let Some((start, _)) = &self.path_recording {
+            actions.push(PendingAction::PathRecording(*start));
+        }
+        if self.awaiting_unlock {
+            actions.push(PendingAction::ResumeOnUnlock);
+        }
+        actions.sort_by_key(|action| (action.due().is_none(), action.due()));
+        actions
+    }
+    
+    fn cancel_pending_action(&mut self, action: PendingAction) {
+        match action {
+            PendingAction::Capture(..) => self.pending_capture = None,
+            // 录制线程无法中断，丢弃句柄后结果不再使用
+            PendingAction::PathRecording(_) => self.path_recording = None,
+            PendingAction::ResumeOnUnlock => self.awaiting_unlock = false,
+        }
+        self.set_status(format!("已取消: {}", action.description()));
+    }
+    
+    // 倒计时结束后读取鼠标位置写入正在编辑的配置
+    fn update_pending_capture(&mut self) {
+        let Some((target, deadline)) = self.pending_capture else {
+            return;
+        };
+        if Instant::now() < deadline {
+            return;
         }
         
-        state.is_running = false;
-        drop(state);
+        self.pending_capture = None;
+        let position = Enigo::new().mouse_location();
         
-        self.status_message = "连点器已停止".to_string();
+        if target == CaptureTarget::Pixel {
+            let Some(condition) = &mut self.editing_config.pixel_condition else {
+                return;
+            };
+            condition.x = position.0;
+            condition.y = position.1;
+            match read_screen_pixel(position.0, position.1) {
+                Some(color) => {
+                    condition.color = color;
+                    self.set_status(format!("已捕获像素 ({}, {}): RGB({}, {}, {})", position.0, position.1, color[0], color[1], color[2]));
+                }
+                None => self.set_status(format!("已捕获坐标 ({}, {})，但无法读取屏幕颜色", position.0, position.1)),
+            }
+            return;
+        }
         
-        if let Some(handle) = self.clicker_thread.take() {
-            // 线程会自行结束，因为我们已经设置了is_running = false
-            let _ = handle.join();
+        if target == CaptureTarget::ClickPosition {
+            self.editing_config.target_position = Some(position);
+        } else if let CaptureTarget::SequencePoint(index) = target {
+            if let Some(point) = self.editing_config.click_points.get_mut(index) {
+                *point = position;
+            }
+        } else if let ActionType::Drag { from, to } = &mut self.editing_config.action_type {
+            match target {
+                CaptureTarget::DragFrom => *from = position,
+                CaptureTarget::DragTo => *to = position,
+                CaptureTarget::ClickPosition | CaptureTarget::SequencePoint(_) | CaptureTarget::Pixel => {}
+            }
         }
+        self.set_status(format!("已捕获坐标: ({}, {})", position.0, position.1));
     }
     
-    fn setup_hotkey(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        // 如果热键已经激活，不需要再次设置
-        if self.hotkey_active {
-            return Ok(());
+    fn start_path_recording(&mut self) {
+        let duration = Duration::from_secs(self.path_record_secs.max(1));
+        let handle = thread::spawn(move || record_mouse_path(CAPTURE_DELAY, duration));
+        self.path_recording = Some((Instant::now() + CAPTURE_DELAY, handle));
+        self.set_status(format!("{}秒后开始录制路径，录制 {} 秒", CAPTURE_DELAY.as_secs(), duration.as_secs()));
+    }
+    
+    fn update_path_recording(&mut self) {
+        let finished = self.path_recording.as_ref().is_some_and(|(_, handle)| handle.is_finished());
+        if !finished {
+            return;
         }
         
-        // 设置热键状态
-        *HOTKEY_ACTIVE.lock().unwrap() = true;
-        
-        // 在新线程中启动热键监听
-        thread::spawn(move || {
-            let mut listener = Listener::new();
-            
-            // 注册F8热键用于开始/停止连点
-            // 根据hotkey库0.3.1版本，使用0作为modifiers表示没有修饰键
-            // 对于F8键，使用虚拟键码0x77（十进制119）
-            if let Ok(_) = listener.register_hotkey(
-                0, // 替换modifiers::NONE
-                0x77, // F8键的虚拟键码
-                move || {
-                    // 通过命令通道发送切换命令
-                    let mut command = HOTKEY_COMMAND.lock().unwrap();
-                    *command = Some(true); // 设置为Some(true)表示需要切换状态
-                },
-            ) {
-                // 开始监听热键
-                listener.listen();
+        let Some((_, handle)) = self.path_recording.take() else {
+            return;
+        };
+        match handle.join() {
+            Ok(path) => {
+                let clicks = path.iter().filter(|point| point.click).count();
+                self.set_status(format!("已录制路径: {} 个采样点，{} 次点击", path.len(), clicks));
+                self.editing_config.path = path;
             }
-        });
-        
-        self.hotkey_active = true;
-        self.status_message = "热键已激活: F8 = 开始/停止".to_string();
+            Err(_) => self.set_status("录制路径失败"),
+        }
+    }
+    
+    // 按距上次点击的时间计算高亮强度，1.0为刚刚点击，0.0为无高亮
+    fn click_flash_intensity(&self) -> f32 {
+        if !self.stats.is_running {
+            return 0.0;
+        }
         
-        Ok(())
+        match self.stats.last_click_time {
+            Some(last_time) => {
+                let elapsed = last_time.elapsed().as_secs_f32();
+                (1.0 - elapsed / CLICK_FLASH_DURATION.as_secs_f32()).max(0.0)
+            }
+            None => 0.0,
+        }
     }
     
     fn get_status_text(&self) -> String {
-        let state = self.state.lock().unwrap();
-        let mut status = format!("状态: {}", if state.is_running { "运行中" } else { "已停止" });
+        let stats = &self.stats;
+        let mut status = match (&self.last_stop_reason, stats.is_running) {
+            (_, true) if stats.paused_at.is_some() => "状态: 已暂停".to_string(),
+            (_, true) => "状态: 运行中".to_string(),
+            (Some(reason), false) => format!("状态: 已停止 ({})", reason.description()),
+            (None, false) => "状态: 已停止".to_string(),
+        };
+        
+        if let Some(elapsed) = stats.elapsed() {
+            status.push_str(&format!(" | 运行时间: {}", format_duration_secs(elapsed.as_secs())));
+            if let Some(limit) = stats.time_limit {
+                if stats.loop_count > 1 {
+                    let loop_elapsed = elapsed.saturating_sub(stats.loop_start_elapsed);
+                    status.push_str(&format!(" (本轮 {} / {})", format_duration_secs(loop_elapsed.as_secs()), format_duration_secs(limit.as_secs())));
+                } else {
+                    status.push_str(&format!(" / {}", format_duration_secs(limit.as_secs())));
+                }
+            }
+            if stats.is_running && stats.paused_at.is_none() && elapsed.as_secs_f64() > 0.0 {
+                status.push_str(&format!(" | 当前速率: {}", format_cps(stats.current_cps)));
+                status.push_str(&format!(" | 平均: {}", format_cps(stats.run_clicks() as f64 / elapsed.as_secs_f64())));
+            }
+        }
+        
+        let key_mode = self.active_config().is_some_and(|config| config.action_type == ActionType::KeyPress);
+        let count_label = match (key_mode, self.settings.reset_count_on_start) {
+            (false, true) => "点击次数",
+            (false, false) => "累计点击",
+            (true, true) => "按键次数",
+            (true, false) => "累计按键",
+        };
+        match stats.click_limit {
+            Some(limit) if self.settings.reset_count_on_start && stats.loop_count <= 1 => status.push_str(&format!(" | {}: {} / {}", count_label, stats.click_count, limit)),
+            Some(limit) if stats.loop_count > 1 => status.push_str(&format!(" | {}: {} (本轮 {} / {})", count_label, stats.click_count, stats.loop_clicks(), limit)),
+            Some(limit) => status.push_str(&format!(" | {}: {} (本次 {} / {})", count_label, stats.click_count, stats.run_clicks(), limit)),
+            None => status.push_str(&format!(" | {}: {}", count_label, stats.click_count)),
+        }
         
-        if let Some(start_time) = state.start_time {
-            let elapsed = start_time.elapsed();
-            status.push_str(&format!(" | 运行时间: {}分{}秒", elapsed.as_secs() / 60, elapsed.as_secs() % 60));
+        if stats.loop_count > 1 {
+            status.push_str(&format!(" | 第 {} / {} 轮", stats.loop_index.min(stats.loop_count), stats.loop_count));
         }
         
-        status.push_str(&format!(" | 点击次数: {}", state.click_count));
+        if stats.skipped_clicks > 0 {
+            status.push_str(&format!(" | 窗口外跳过: {}", stats.skipped_clicks));
+        }
+        
+        if stats.is_running && stats.rate_limited {
+            status.push_str(" | 已被CPS上限限速");
+        }
         
-        if let Some(last_time) = state.last_click_time {
+        if let Some(last_time) = stats.last_click_time {
             status.push_str(&format!(" | 上次点击: {}毫秒前", last_time.elapsed().as_millis()));
         }
         
@@ -395,17 +4437,79 @@ impl SeriousClickerApp {
 // 实现eframe的App trait
 impl eframe::App for SeriousClickerApp {
     
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        // 以--minimized启动时在首帧最小化
+        if self.start_minimized {
+            frame.set_minimized(true);
+            self.start_minimized = false;
+        }
+        
         // 检查热键命令
         {
             let mut command = HOTKEY_COMMAND.lock().unwrap();
             if command.is_some() {
-                // 收到热键命令，切换连点器状态
-                self.toggle_clicker();
                 *command = None; // 重置命令
+                if self.settings.suppress_hotkeys_in_dialogs && self.modal_open() {
+                    // 编辑或确认窗口打开时不响应，避免在对话框上方开始点击
+                    self.set_status("对话框打开时已忽略热键");
+                } else if self.settings.suppress_hotkeys_while_typing && ctx.wants_keyboard_input() {
+                    // 全局热键不管窗口焦点，输入配置名或序列时按到热键不应开始点击
+                    self.set_status("正在输入文字，已忽略热键");
+                } else {
+                    // 收到热键命令，切换连点器状态
+                    self.toggle_clicker(StopReason::Hotkey);
+                    self.hotkey_feedback();
+                }
+            }
+        }
+        
+        self.check_hotkey_registration();
+        self.reload_configs_if_changed();
+        
+        // 处理命令端口转来的命令
+        while let Ok((command, reply)) = self.remote_receiver.try_recv() {
+            let response = self.handle_remote_command(command);
+            let _ = reply.send(response);
+        }
+        if let Some(err) = COMMAND_SERVER_ERROR.lock().unwrap().take() {
+            self.set_status(format!("命令端口接受连接失败: {}", err));
+        }
+        
+        // 读取点击线程发来的最新统计，线程自行停止时显示原因
+        self.recover_crashed_clicker();
+        if STATE_POISONED.swap(false, Ordering::Relaxed) {
+            self.set_status("点击线程持锁时异常退出，已恢复点击状态");
+        }
+        
+        while let Ok(mut stats) = self.stats_receiver.try_recv() {
+            if let Some(reason) = stats.stop_reason.take() {
+                // 有上限的运行自行完成时提醒用户
+                if stats.run_completed {
+                    if self.settings.notify_on_completion {
+                        show_desktop_notification(format!("完成: 点击 {} 次，{}", stats.run_clicks(), reason.description()));
+                    }
+                    if self.settings.sound_on_completion {
+                        play_alert_sound();
+                    }
+                }
+                self.awaiting_unlock = reason == StopReason::SessionLocked && self.settings.resume_on_unlock;
+                self.record_stop(reason);
+            }
+            self.stats = stats;
+        }
+        
+        // 锁屏停止后解锁，自动恢复运行
+        if self.awaiting_unlock && session_locked() == Some(false) {
+            self.awaiting_unlock = false;
+            self.start_clicker();
+            if self.stats.is_running {
+                self.set_status("屏幕已解锁，已恢复运行");
             }
         }
         
+        self.update_pending_capture();
+        self.update_path_recording();
+        
         // 设置视觉风格
         let mut style = (*ctx.style()).clone();
         style.spacing.item_spacing = egui::vec2(10.0, 10.0);
@@ -422,39 +4526,245 @@ impl eframe::App for SeriousClickerApp {
                 ui.menu_button("文件", |ui| {
                     if ui.button("保存配置").clicked() {
                         if let Err(err) = self.save_configs() {
-                            self.status_message = format!("保存配置失败: {}", err);
+                            self.set_status(format!("保存配置失败: {}", err));
                         } else {
-                            self.status_message = "配置已保存".to_string();
+                            self.set_status("配置已保存");
                         }
                         ui.close_menu();
                     }
                     
+                    if ui.button("导入配置...").clicked() {
+                        self.import_configs();
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button("导出配置...").on_hover_text("把所有配置导出到一个JSON文件，可以分享给别人导入").clicked() {
+                        let indices: Vec<usize> = (0..self.configs.len()).collect();
+                        self.export_configs(&indices);
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button("导入其他连点器配置...").clicked() {
+                        self.import_foreign_configs();
+                        ui.close_menu();
+                    }
+                    
                     if ui.button("退出").clicked() {
-                        self.stop_clicker();
+                        self.stop_clicker(StopReason::Exit);
                         std::process::exit(0);
                     }
                 });
                 
                 ui.menu_button("热键", |ui| {
                     if ui.button(if self.hotkey_active { "禁用热键" } else { "启用热键" }).clicked() {
-                        if self.hotkey_active {
-                            // 禁用热键
-                            *HOTKEY_ACTIVE.lock().unwrap() = false;
-                            self.hotkey_active = false;
-                            self.status_message = "热键已禁用".to_string();
-                        } else {
-                            // 启用热键
-                            if let Err(err) = self.setup_hotkey() {
-                                self.status_message = format!("设置热键失败: {}", err);
+                        self.set_hotkeys_enabled(!self.hotkey_active);
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button(format!("更改热键 ({})...", self.hotkey_name())).clicked() {
+                        self.capturing_hotkey = true;
+                        ui.close_menu();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.suppress_hotkeys_in_dialogs, "对话框打开时忽略热键").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.suppress_hotkeys_while_typing, "输入文字时忽略热键").changed() {
+                        self.save_settings();
+                    }
+                    
+                    ui.menu_button("诊断信息", |ui| {
+                        for line in self.hotkey_diagnostics() {
+                            ui.label(RichText::new(line).text_style(egui::TextStyle::Monospace));
+                        }
+                    });
+                });
+                
+                ui.menu_button("设置", |ui| {
+                    let mut autostart = self.autostart_enabled;
+                    if ui.checkbox(&mut autostart, "开机自启动").clicked() {
+                        match set_autostart(autostart) {
+                            Ok(()) => {
+                                self.autostart_enabled = autostart;
+                                self.set_status(if autostart { "已启用开机自启动" } else { "已关闭开机自启动" });
+                            }
+                            Err(err) => {
+                                self.set_status(format!("设置开机自启动失败: {}", err));
                             }
                         }
                         ui.close_menu();
                     }
+                    
+                    if ui.checkbox(&mut self.settings.self_test_on_startup, "启动时自检输入").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.notify_on_completion, "完成时通知").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.sound_on_completion, "完成时提示音").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.hotkey_feedback_sound, "热键提示音").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.hotkey_feedback_notification, "热键通知").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.emergency_exit_enabled, "强制退出 (Ctrl+Alt+F8)")
+                        .on_hover_text("松开所有鼠标按键并立即结束程序，用于点击失控时；需要先启用热键")
+                        .changed()
+                    {
+                        EMERGENCY_EXIT_ENABLED.store(self.settings.emergency_exit_enabled, Ordering::Relaxed);
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.reset_count_on_start, "开始时清零点击数").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.reload_configs_on_change, "外部修改时重新加载配置").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.allow_continuous_mode, "允许连续模式").on_hover_text("连续模式以目标CPS全速点击，开启后才能在编辑窗口中选择").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.freeze_edits_while_running, "运行中锁定配置编辑").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.delete_key_deletes_config, "Delete键删除配置").on_hover_text("按Delete键删除选中的配置，删除前仍会确认").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.high_precision_timing, "高精度计时")
+                        .on_hover_text("间隔的最后一小段改为忙等，短间隔更准确，但会多占用一个CPU核心；下次开始时生效")
+                        .changed()
+                    {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.stop_on_session_lock, "锁屏时停止").on_hover_text("锁屏或屏保启动时停止点击；目前仅支持Windows").changed() {
+                        lock_state(&self.state).stop_on_session_lock = self.settings.stop_on_session_lock;
+                        self.save_settings();
+                    }
+                    
+                    ui.add_enabled_ui(self.settings.stop_on_session_lock, |ui| {
+                        if ui.checkbox(&mut self.settings.resume_on_unlock, "解锁后自动恢复").changed() {
+                            if !self.settings.resume_on_unlock {
+                                self.awaiting_unlock = false;
+                            }
+                            self.save_settings();
+                        }
+                    });
+                    
+                    if ui.checkbox(&mut self.settings.allow_multiple_instances, "允许多开").on_hover_text("下次启动时生效").changed() {
+                        self.save_settings();
+                    }
+                    
+                    if ui.checkbox(&mut self.settings.record_usage_history, "记录使用历史").on_hover_text("在本地记录每次运行的配置、时长和点击数，用于使用统计，不会上传").changed() {
+                        self.save_settings();
+                    }
+                    
+                    let mut command_server = self.command_server.is_some();
+                    if ui.checkbox(&mut command_server, "本地命令端口").on_hover_text("通过 127.0.0.1 上的TCP端口接收 START/STOP/TOGGLE/SELECT/STATUS 命令").changed() {
+                        self.settings.command_server_enabled = command_server;
+                        if command_server {
+                            self.start_command_server();
+                        } else {
+                            self.stop_command_server();
+                            self.set_status("命令端口已关闭");
+                        }
+                        self.save_settings();
+                    }
+                    
+                    ui.horizontal(|ui| {
+                        ui.label("端口:");
+                        if ui.add(egui::DragValue::new(&mut self.settings.command_port).clamp_range(1024..=65535)).changed() {
+                            self.save_settings();
+                        }
+                        if self.command_server.is_some() && ui.button("重新开启").clicked() {
+                            self.start_command_server();
+                        }
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        let mut seeded = self.settings.rng_seed.is_some();
+                        if ui.checkbox(&mut seeded, "固定随机种子").on_hover_text("每次运行产生相同的随机间隔序列，用于测试和复现问题").changed() {
+                            self.settings.rng_seed = if seeded { Some(0) } else { None };
+                            self.save_settings();
+                        }
+                        if let Some(seed) = &mut self.settings.rng_seed {
+                            if ui.add(egui::DragValue::new(seed)).changed() {
+                                self.save_settings();
+                            }
+                        }
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        ui.label("CPS小数位:");
+                        if ui.add(egui::DragValue::new(&mut self.settings.cps_decimals).clamp_range(0..=4)).changed() {
+                            CPS_DECIMALS.store(self.settings.cps_decimals, Ordering::Relaxed);
+                            self.save_settings();
+                        }
+                    });
+                    
+                    ui.horizontal(|ui| {
+                        ui.label("最大CPS:");
+                        if ui.add(egui::DragValue::new(&mut self.settings.max_cps).speed(1.0).clamp_range(1.0..=1000.0)).changed() {
+                            lock_state(&self.state).app_max_cps = self.settings.max_cps;
+                            self.save_settings();
+                        }
+                    });
+                    
+                    ui.menu_button("表格列", |ui| {
+                        for column in GridColumn::all() {
+                            let mut visible = !self.settings.hidden_columns.contains(&column);
+                            if ui.checkbox(&mut visible, column.name()).changed() {
+                                if visible {
+                                    self.settings.hidden_columns.retain(|&hidden| hidden != column);
+                                } else {
+                                    self.settings.hidden_columns.push(column);
+                                }
+                                self.save_settings();
+                            }
+                        }
+                        
+                        if ui.button("恢复原始顺序").clicked() {
+                            self.settings.sort_column = None;
+                            self.settings.sort_descending = false;
+                            self.save_settings();
+                            ui.close_menu();
+                        }
+                    });
                 });
                 
                 ui.menu_button("帮助", |ui| {
+                    if ui.button("输入自检").clicked() {
+                        self.self_test();
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button("诊断").clicked() {
+                        self.diagnostics = Some(self.diagnostics_report());
+                        ui.close_menu();
+                    }
+                    
+                    if ui.button("使用统计").clicked() {
+                        self.show_usage_dashboard = true;
+                        ui.close_menu();
+                    }
+                    
                     if ui.button("关于").clicked() {
-                        self.status_message = "SeriousClick 专业连点器 v0.1.0".to_string();
+                        let mode = if CONFIG_LOCATION.portable { "（便携模式）" } else { "" };
+                        self.set_status(format!("SeriousClick 专业连点器 v0.1.0 | 配置目录{}: {}", mode, CONFIG_LOCATION.dir.display()));
                         ui.close_menu();
                     }
                 });
@@ -463,27 +4773,114 @@ impl eframe::App for SeriousClickerApp {
         
         // 底部状态栏
         egui::TopBottomPanel::bottom("bottom_panel").show(ctx, |ui| {
+            egui::CollapsingHeader::new(format!("消息记录 ({})", self.status_history.len()))
+                .id_source("status_history")
+                .show(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(120.0)
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            for (time, message) in &self.status_history {
+                                let elapsed = time.elapsed().as_secs() as i64;
+                                let ago = if elapsed < 60 { format!("{}秒前", elapsed) } else { format_elapsed_ago(elapsed) };
+                                ui.label(RichText::new(format!("[{}] {}", ago, message))
+                                    .text_style(egui::TextStyle::Monospace));
+                            }
+                        });
+                });
+            
+            let pending_actions = self.pending_actions();
+            if !pending_actions.is_empty() {
+                let mut cancelled = None;
+                egui::CollapsingHeader::new(format!("计划 ({})", pending_actions.len()))
+                    .id_source("pending_actions")
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        for action in pending_actions {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(action.description()).text_style(egui::TextStyle::Monospace));
+                                if ui.small_button("取消").clicked() {
+                                    cancelled = Some(action);
+                                }
+                            });
+                        }
+                    });
+                if let Some(action) = cancelled {
+                    self.cancel_pending_action(action);
+                }
+            }
+            
             ui.horizontal(|ui| {
                 ui.label(RichText::new(&status_text).text_style(egui::TextStyle::Monospace));
                 ui.separator();
                 ui.label(RichText::new(&self.status_message).text_style(egui::TextStyle::Monospace));
                 
-                if self.hotkey_active {
+                let focus = self.active_config()
+                    .filter(|config| !config.target_window_title.is_empty())
+                    .and_then(|config| target_window_focus(&config.target_window_title));
+                if let Some(focus) = focus {
+                    let color = match focus {
+                        WindowFocus::Focused => Color32::GREEN,
+                        WindowFocus::Unfocused => Color32::YELLOW,
+                        WindowFocus::NotFound => Color32::RED,
+                    };
+                    ui.separator();
+                    ui.label(RichText::new(format!("目标窗口: {}", focus.name())).color(color).text_style(egui::TextStyle::Monospace));
+                }
+                
+                if self.hotkey_active && !self.hotkey_registration_pending {
                     ui.separator();
-                    ui.label(RichText::new("热键: F8 = 开始/停止").text_style(egui::TextStyle::Monospace));
+                    ui.label(RichText::new(format!("热键: {} = 开始/停止", self.hotkey_name())).text_style(egui::TextStyle::Monospace));
                 }
             });
         });
         
+        // 点击闪烁高亮，高速点击时表现为持续发光
+        let flash = self.click_flash_intensity();
+        
         // 主界面
         egui::CentralPanel::default().show(ctx, |ui| {
             // 配置列表和控制按钮
             ui.horizontal(|ui| {
-                if ui.add(egui::Button::new(if self.state.lock().unwrap().is_running { "停止 ⏹" } else { "开始 ▶" })
+                let base_fill = ui.visuals().widgets.inactive.bg_fill;
+                let flash_fill = Color32::from_rgb(60, 180, 90);
+                let mix = |base: u8, highlight: u8| (base as f32 + (highlight as f32 - base as f32) * flash) as u8;
+                let button_fill = Color32::from_rgb(
+                    mix(base_fill.r(), flash_fill.r()),
+                    mix(base_fill.g(), flash_fill.g()),
+                    mix(base_fill.b(), flash_fill.b()),
+                );
+                
+                if ui.add(egui::Button::new(if self.stats.is_running { "停止 ⏹" } else { "开始 ▶" })
+                    .fill(button_fill)
                     .min_size(Vec2::new(100.0, 30.0)))
                     .clicked() 
                 {
-                    self.toggle_clicker();
+                    self.toggle_clicker(StopReason::Manual);
+                }
+                
+                ui.add_enabled_ui(self.stats.is_running, |ui| {
+                    let label = if self.stats.paused_at.is_some() { "继续 ⏵" } else { "暂停 ⏸" };
+                    if ui.button(label).on_hover_text("暂停时保留点击数和运行时间；热键仍然是开始/停止").clicked() {
+                        self.toggle_pause();
+                    }
+                });
+                
+                // 临时按次数运行，不修改配置
+                ui.add_enabled_ui(!self.stats.is_running, |ui| {
+                    ui.add(egui::DragValue::new(&mut self.quick_click_target).clamp_range(1..=1_000_000));
+                    if ui.button(format!("点击 {} 次", self.quick_click_target)).clicked() {
+                        self.start_run(Some(self.quick_click_target));
+                    }
+                });
+                
+                if ui.button("清零").clicked() {
+                    let mut state = lock_state(&self.state);
+                    state.click_count = 0;
+                    state.skipped_clicks = 0;
+                    state.run_start_count = 0;
+                    state.loop_start_count = 0;
+                    self.stats = state.stats();
                 }
                 
                 ui.separator();
@@ -492,47 +4889,64 @@ impl eframe::App for SeriousClickerApp {
                     .selected_text(if self.configs.is_empty() {
                         "无配置".to_string()
                     } else {
-                        self.configs[self.selected_config_index].name.clone()
+                        self.configs[self.selected_config_index].display_name()
                     })
                     .show_ui(ui, |ui| {
                         let mut config_to_apply = None;
                         for (i, config) in self.configs.iter().enumerate() {
-                            if ui.selectable_value(&mut self.selected_config_index, i, &config.name).clicked() {
+                            if ui.selectable_value(&mut self.selected_config_index, i, config.display_name()).clicked() {
                                 config_to_apply = Some(config.clone());
                             }
                         }
                         
                         if let Some(config) = config_to_apply {
                             self.apply_config(config);
+                            self.touch_selected_config();
                         }
                     });
                 
                 if ui.button("New").clicked() {
                     self.editing_config = ClickerConfig::default();
                     self.editing_config.name = format!("Config {}", self.configs.len() + 1);
+                    self.editing_index = None;
                     self.is_editing = true;
+                    self.capturing_press_key = false;
                 }
                 
                 if !self.configs.is_empty() {
                     if ui.button("Edit").clicked() {
                         self.editing_config = self.configs[self.selected_config_index].clone();
+                        self.editing_index = Some(self.selected_config_index);
                         self.is_editing = true;
+                        self.capturing_press_key = false;
                     }
                     
                     if ui.button("Delete").clicked() && !self.configs.is_empty() {
-                        self.configs.remove(self.selected_config_index);
-                        if self.configs.is_empty() {
-                            self.configs.push(ClickerConfig::default());
-                        }
-                        self.selected_config_index = self.selected_config_index.min(self.configs.len() - 1);
-                        self.apply_config(self.configs[self.selected_config_index].clone());
-                        let _ = self.save_configs();
+                        self.pending_delete = true;
                     }
                 }
             });
             
             ui.add_space(10.0);
             
+            // 批量操作工具栏
+            if !self.selected_rows.is_empty() {
+                ui.horizontal(|ui| {
+                    ui.label(format!("已选择 {} 项", self.selected_rows.len()));
+                    if ui.button("删除所选").clicked() {
+                        self.pending_bulk_delete = true;
+                    }
+                    if ui.button("导出所选").clicked() {
+                        let mut indices: Vec<usize> = self.selected_rows.iter().copied().collect();
+                        indices.sort_unstable();
+                        self.export_configs(&indices);
+                    }
+                    if ui.button("取消选择").clicked() {
+                        self.selected_rows.clear();
+                    }
+                });
+            }
+            
             // 配置表格
             egui::Frame::dark_canvas(ui.style()).show(ui, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
@@ -541,37 +4955,56 @@ impl eframe::App for SeriousClickerApp {
                         .spacing([10.0, 5.0])
                         .min_col_width(100.0)
                         .show(ui, |ui| {
-                            // 表头
-                            ui.label(RichText::new("No.").strong());
-                            ui.label(RichText::new("Window Title").strong());
-                            ui.label(RichText::new("Click Type").strong());
-                            ui.label(RichText::new("Interval").strong());
+                            let columns: Vec<GridColumn> = GridColumn::all()
+                                .into_iter()
+                                .filter(|column| !self.settings.hidden_columns.contains(column))
+                                .collect();
+                            
+                            // 全选
+                            let mut all_selected = self.selected_rows.len() == self.configs.len();
+                            if ui.checkbox(&mut all_selected, "").changed() {
+                                self.selected_rows = if all_selected { (0..self.configs.len()).collect() } else { HashSet::new() };
+                            }
+                            
+                            // 表头，点击排序
+                            for &column in &columns {
+                                let mut header = column.name().to_string();
+                                if self.settings.sort_column == Some(column) {
+                                    header.push_str(if self.settings.sort_descending { " ▼" } else { " ▲" });
+                                }
+                                if ui.add(egui::Label::new(RichText::new(header).strong()).sense(egui::Sense::click())).clicked() {
+                                    self.toggle_sort(column);
+                                }
+                            }
                             ui.end_row();
                             
                             // 配置行
-                            for (i, config) in self.configs.iter().enumerate() {
+                            for i in self.sorted_config_indices() {
+                                let mut checked = self.selected_rows.contains(&i);
+                                if ui.checkbox(&mut checked, "").changed() {
+                                    if checked {
+                                        self.selected_rows.insert(i);
+                                    } else {
+                                        self.selected_rows.remove(&i);
+                                    }
+                                }
+                                
+                                let config = &self.configs[i];
                                 let is_selected = i == self.selected_config_index;
                                 let row_color = if is_selected { Color32::from_rgb(60, 100, 150) } else { ui.style().visuals.widgets.noninteractive.bg_fill };
                                 
                                 ui.scope(|ui| {
                                     ui.style_mut().visuals.widgets.noninteractive.bg_fill = row_color;
-                                    ui.label(RichText::new(format!("{}", i + 1)).strong());
-                                    ui.label(&config.name);
-                                    ui.label(config.click_mode.name());
-                                    
-                                    let interval_text = match config.click_mode {
-                                        ClickMode::FixedInterval => format!("{} ms", config.fixed_interval_ms),
-                                        ClickMode::RandomInterval => format!("{}-{} ms", config.min_random_interval_ms, config.max_random_interval_ms),
-                                        ClickMode::Continuous => "Continuous".to_string(),
-                                        ClickMode::Pattern => {
-                                            let intervals = config.pattern_intervals.iter()
-                                                .map(|i| i.to_string())
-                                                .collect::<Vec<_>>()
-                                                .join(",");
-                                            format!("[{}] ms", intervals)
-                                        }
-                                    };
-                                    ui.label(interval_text);
+                                    for &column in &columns {
+                                        match column {
+                                            GridColumn::Number => ui.label(RichText::new(format!("{}", i + 1)).strong()),
+                                            GridColumn::Name => ui.label(config.display_name()),
+                                            GridColumn::ClickType => ui.label(config.click_mode.name()),
+                                            GridColumn::Interval => ui.label(config.interval_text()),
+                                            GridColumn::RunMode => ui.label(config.run_mode_text()),
+                                            GridColumn::LastUsed => ui.label(config.last_used_text()),
+                                        };
+                                    }
                                 });
                                 ui.end_row();
                             }
@@ -585,115 +5018,925 @@ impl eframe::App for SeriousClickerApp {
                     .collapsible(false)
                     .resizable(false)
                     .show(ctx, |ui| {
-                        ui.horizontal(|ui| {
-                            ui.label("Config Name:");
-                            ui.text_edit_singleline(&mut self.editing_config.name);
-                        });
-                        
-                        ui.add_space(5.0);
+                        // 运行中锁定编辑时，修改只能等停止后进行
+                        let frozen = self.settings.freeze_edits_while_running && self.stats.is_running;
+                        if frozen {
+                            ui.label(RichText::new("运行中已锁定编辑，停止后才能修改").color(Color32::YELLOW));
+                        }
                         
-                        ui.horizontal(|ui| {
-                            ui.label("Click Mode:");
-                            egui::ComboBox::from_id_source("click_mode")
-                                .selected_text(self.editing_config.click_mode.name())
-                                .show_ui(ui, |ui| {
-                                    for mode in ClickMode::all() {
-                                        ui.selectable_value(&mut self.editing_config.click_mode, mode, mode.name());
+                        ui.add_enabled_ui(!frozen, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label("Config Name:").on_hover_text("配置的名称，显示在列表和下拉框中");
+                                ui.text_edit_singleline(&mut self.editing_config.name);
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Icon:").on_hover_text("显示在名称前的图标，方便区分配置");
+                                if ui.selectable_label(self.editing_config.icon.is_empty(), "None").clicked() {
+                                    self.editing_config.icon.clear();
+                                }
+                                for icon in CONFIG_ICONS {
+                                    if ui.selectable_label(self.editing_config.icon == icon, icon).clicked() {
+                                        self.editing_config.icon = icon.to_string();
+                                    }
+                                }
+                            });
+                            
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Click Mode:").on_hover_text(self.editing_config.click_mode.description());
+                                egui::ComboBox::from_id_source("click_mode")
+                                    .selected_text(self.editing_config.click_mode.name())
+                                    .show_ui(ui, |ui| {
+                                        let mut modes = ClickMode::available(self.settings.allow_continuous_mode);
+                                        // 已经是连续模式的旧配置仍然显示当前选项
+                                        if !modes.contains(&self.editing_config.click_mode) {
+                                            modes.push(self.editing_config.click_mode);
+                                        }
+                                        for mode in modes {
+                                            let selected = self.editing_config.click_mode == mode;
+                                            if ui.selectable_label(selected, mode.name()).on_hover_text(mode.description()).clicked() && !selected {
+                                                self.editing_config.switch_mode(mode);
+                                            }
+                                        }
+                                    });
+                            });
+                            
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Mouse Button:").on_hover_text("点击时使用的鼠标按键");
+                                egui::ComboBox::from_id_source("mouse_button")
+                                    .selected_text(self.editing_config.mouse_button.name())
+                                    .show_ui(ui, |ui| {
+                                        for button in MouseButtonType::all() {
+                                            ui.selectable_value(&mut self.editing_config.mouse_button, button, button.name());
+                                        }
+                                    });
+                            });
+                            
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Action:").on_hover_text(self.editing_config.action_type.description());
+                                egui::ComboBox::from_id_source("action_type")
+                                    .selected_text(self.editing_config.action_type.name())
+                                    .show_ui(ui, |ui| {
+                                        for action in ActionType::all() {
+                                            let selected = std::mem::discriminant(&self.editing_config.action_type) == std::mem::discriminant(&action);
+                                            if ui.selectable_label(selected, action.name()).on_hover_text(action.description()).clicked() && !selected {
+                                                self.editing_config.action_type = action;
+                                                // 组合按键默认从当前选中的单个按键开始
+                                                if action == ActionType::ButtonCombo && self.editing_config.combo_buttons.is_empty() {
+                                                    self.editing_config.combo_buttons.push(self.editing_config.mouse_button);
+                                                }
+                                            }
+                                        }
+                                    });
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Post-click Delay:").on_hover_text("每次点击（拖拽、路径结束）后额外等待的时间，让目标程序有时间处理点击；不影响点击间隔");
+                                ui.add(egui::DragValue::new(&mut self.editing_config.post_click_delay_ms).clamp_range(0..=MAX_FIXED_INTERVAL_MS).suffix(" ms"));
+                            });
+                            
+                            if matches!(self.editing_config.action_type, ActionType::MouseClick | ActionType::PointSequence) {
+                                ui.horizontal(|ui| {
+                                    ui.label("Clicks per Action:").on_hover_text("每次连续点击几下：2为双击，3为三击；点击次数按单次点击统计");
+                                    ui.add(egui::Slider::new(&mut self.editing_config.clicks_per_action, 1..=MAX_CLICKS_PER_ACTION));
+                                });
+                                if self.editing_config.clicks_per_action > 1 {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Multi-click Gap:").on_hover_text("连续点击之间的间隔；超过系统双击判定时间会被当成多次单击");
+                                        ui.add(egui::Slider::new(&mut self.editing_config.multi_click_gap_ms, 0..=MAX_MULTI_CLICK_GAP_MS).suffix(" ms"));
+                                    });
+                                }
+                            }
+                            
+                            if self.editing_config.action_type == ActionType::MouseClick {
+                                ui.horizontal(|ui| {
+                                    ui.label("Cursor Offset:").on_hover_text("在鼠标当前位置（或下面的固定坐标）加上偏移（像素）处点击，点击后移回；(0, 0) 表示直接在鼠标处点击");
+                                    ui.label("dx");
+                                    ui.add(egui::DragValue::new(&mut self.editing_config.cursor_offset.0));
+                                    ui.label("dy");
+                                    ui.add(egui::DragValue::new(&mut self.editing_config.cursor_offset.1));
+                                });
+                                
+                                let mut fixed = self.editing_config.target_position.is_some();
+                                if ui.checkbox(&mut fixed, "Click at a fixed position")
+                                    .on_hover_text("每次把鼠标移到固定的屏幕坐标点击，然后移回原位，点击时可以继续在其他窗口工作")
+                                    .changed()
+                                {
+                                    self.editing_config.target_position = if fixed { Some(Enigo::new().mouse_location()) } else { None };
+                                }
+                                let mut paste_error = None;
+                                if let Some(point) = &mut self.editing_config.target_position {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Position:").on_hover_text("屏幕绝对坐标（像素），可以捕获鼠标位置或从剪贴板粘贴");
+                                        ui.label("X");
+                                        ui.add(egui::DragValue::new(&mut point.0));
+                                        ui.label("Y");
+                                        ui.add(egui::DragValue::new(&mut point.1));
+                                        
+                                        match self.pending_capture {
+                                            Some((CaptureTarget::ClickPosition, deadline)) => {
+                                                let remaining = deadline.saturating_duration_since(Instant::now());
+                                                ui.label(format!("{}秒后捕获...", remaining.as_secs() + 1));
+                                            }
+                                            _ => {
+                                                if ui.button("Capture").on_hover_text("倒计时结束时读取鼠标位置").clicked() {
+                                                    self.pending_capture = Some((CaptureTarget::ClickPosition, Instant::now() + CAPTURE_DELAY));
+                                                }
+                                            }
+                                        }
+                                        
+                                        if ui.button("从剪贴板粘贴坐标").clicked() {
+                                            match paste_coordinate() {
+                                                Ok(position) => *point = position,
+                                                Err(err) => paste_error = Some(err),
+                                            }
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Position Jitter:").on_hover_text("每次点击在固定坐标附近随机偏移，最多偏移这么多像素，偏移多集中在中心附近；0表示总是点击同一个像素");
+                                        ui.add(egui::Slider::new(&mut self.editing_config.position_jitter_px, 0..=50).suffix(" px"));
+                                    });
+                                }
+                                if let Some(err) = paste_error {
+                                    self.set_status(err);
+                                }
+                            }
+                            
+                            if self.editing_config.action_type == ActionType::KeyPress {
+                                ui.horizontal(|ui| {
+                                    ui.label("Key:").on_hover_text("每次按下并松开的键盘按键：字母和数字用Capture捕获，功能键、小键盘、方向键和媒体键在下拉框中选择");
+                                    ui.label(RichText::new(&self.editing_config.press_key).text_style(egui::TextStyle::Monospace));
+                                    if self.capturing_press_key {
+                                        ui.label("请按下按键... (Esc取消)");
+                                        let pressed = ui.input(|i| {
+                                            i.events.iter().find_map(|event| match event {
+                                                egui::Event::Key { key, pressed: true, .. } => Some(*key),
+                                                _ => None,
+                                            })
+                                        });
+                                        match pressed {
+                                            Some(egui::Key::Escape) => self.capturing_press_key = false,
+                                            Some(pressed) => match HOTKEY_KEYS.iter().find(|(key, _, _)| *key == pressed) {
+                                                Some((_, _, name)) if enigo_key(name).is_some() => {
+                                                    self.editing_config.press_key = name.to_string();
+                                                    self.capturing_press_key = false;
+                                                }
+                                                _ => self.set_status("不支持这个按键"),
+                                            },
+                                            None => {}
+                                        }
+                                    } else if ui.button("Capture").clicked() {
+                                        self.capturing_press_key = true;
                                     }
+                                    egui::ComboBox::from_id_source("press_key")
+                                        .selected_text("More keys")
+                                        .show_ui(ui, |ui| {
+                                            for (group, names) in PRESS_KEY_GROUPS {
+                                                ui.label(RichText::new(group).strong());
+                                                for &name in names {
+                                                    let selected = self.editing_config.press_key == name;
+                                                    if ui.add_enabled(enigo_key(name).is_some(), egui::SelectableLabel::new(selected, name))
+                                                        .on_disabled_hover_text("当前系统不支持这个按键")
+                                                        .clicked()
+                                                    {
+                                                        self.editing_config.press_key = name.to_string();
+                                                        self.capturing_press_key = false;
+                                                    }
+                                                }
+                                            }
+                                        });
                                 });
-                        });
-                        
-                        ui.add_space(5.0);
-                        
-                        ui.horizontal(|ui| {
-                            ui.label("Mouse Button:");
-                            egui::ComboBox::from_id_source("mouse_button")
-                                .selected_text(self.editing_config.mouse_button.name())
-                                .show_ui(ui, |ui| {
+                                ui.checkbox(&mut self.editing_config.key_repeat, "Hold with auto-repeat")
+                                    .on_hover_text("按住按键不松开，像物理按键一样先等待首次延迟，再按重复间隔自动重复；忽略点击模式的间隔，停止或暂停时松开");
+                                if self.editing_config.key_repeat {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Repeat Delay:").on_hover_text("按下后到开始自动重复的时间");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.key_repeat_delay_ms).clamp_range(0..=5000).suffix(" ms"));
+                                        ui.label("Repeat Interval:").on_hover_text("自动重复时两次按下事件的间隔");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.key_repeat_interval_ms).clamp_range(1..=1000).suffix(" ms"));
+                                    });
+                                }
+                            }
+                            
+                            if self.editing_config.action_type == ActionType::ButtonCombo {
+                                ui.horizontal(|ui| {
+                                    ui.label("Combo Buttons:").on_hover_text("同时按下的鼠标按键；都不选时使用上面的 Mouse Button");
                                     for button in MouseButtonType::all() {
-                                        ui.selectable_value(&mut self.editing_config.mouse_button, button, button.name());
+                                        let mut checked = self.editing_config.combo_buttons.contains(&button);
+                                        if ui.checkbox(&mut checked, button.name()).changed() {
+                                            if checked {
+                                                self.editing_config.combo_buttons.push(button);
+                                            } else {
+                                                self.editing_config.combo_buttons.retain(|b| *b != button);
+                                            }
+                                        }
                                     }
                                 });
-                        });
-                        
-                        ui.add_space(5.0);
-                        
-                        // 根据点击模式显示不同的配置选项
-                        match self.editing_config.click_mode {
-                            ClickMode::FixedInterval => {
+                            }
+                            
+                            if self.editing_config.action_type == ActionType::FollowPath {
+                                let mut start_recording = false;
                                 ui.horizontal(|ui| {
-                                    ui.label("Click Interval (ms):");
-                                    ui.add(egui::Slider::new(&mut self.editing_config.fixed_interval_ms, 1..=1000));
+                                    let path = &self.editing_config.path;
+                                    let duration_ms = path.last().map_or(0, |point| point.t_ms);
+                                    ui.label(format!("Path: {} points, {:.1}s", path.len(), duration_ms as f64 / 1000.0))
+                                        .on_hover_text("录制的鼠标轨迹，回放时按录制速度移动");
+                                    
+                                    match &self.path_recording {
+                                        Some((start, _)) => {
+                                            let now = Instant::now();
+                                            if now < *start {
+                                                ui.label(format!("{}秒后开始录制...", start.duration_since(now).as_secs() + 1));
+                                            } else {
+                                                ui.label(format!("录制中... {}秒", now.duration_since(*start).as_secs()));
+                                            }
+                                        }
+                                        None => {
+                                            ui.add(egui::DragValue::new(&mut self.path_record_secs).clamp_range(1..=300).suffix("s"))
+                                                .on_hover_text("录制时长（秒）");
+                                            start_recording = ui.button("录制路径").clicked();
+                                            if !self.editing_config.path.is_empty() && ui.button("清除").clicked() {
+                                                self.editing_config.path.clear();
+                                            }
+                                        }
+                                    }
                                 });
-                            },
-                            ClickMode::RandomInterval => {
+                                if start_recording {
+                                    self.start_path_recording();
+                                }
+                            }
+                            
+                            let mut paste_error = None;
+                            if let ActionType::Drag { from, to } = &mut self.editing_config.action_type {
+                                for (label, point, target) in [("Drag From:", from, CaptureTarget::DragFrom), ("Drag To:", to, CaptureTarget::DragTo)] {
+                                    ui.horizontal(|ui| {
+                                        ui.label(label).on_hover_text("屏幕绝对坐标（像素），可以捕获鼠标位置或从剪贴板粘贴");
+                                        ui.label("X");
+                                        ui.add(egui::DragValue::new(&mut point.0));
+                                        ui.label("Y");
+                                        ui.add(egui::DragValue::new(&mut point.1));
+                                        
+                                        match self.pending_capture {
+                                            Some((pending, deadline)) if pending == target => {
+                                                let remaining = deadline.saturating_duration_since(Instant::now());
+                                                ui.label(format!("{}秒后捕获...", remaining.as_secs() + 1));
+                                            }
+                                            _ => {
+                                                if ui.button("Capture").clicked() {
+                                                    self.pending_capture = Some((target, Instant::now() + CAPTURE_DELAY));
+                                                }
+                                            }
+                                        }
+                                        
+                                        if ui.button("从剪贴板粘贴坐标").clicked() {
+                                            match paste_coordinate() {
+                                                Ok(position) => *point = position,
+                                                Err(err) => paste_error = Some(err),
+                                            }
+                                        }
+                                    });
+                                }
+                            }
+                            if self.editing_config.action_type == ActionType::PointSequence {
+                                ui.label("Click Points:").on_hover_text("屏幕绝对坐标（像素），每次点击移到下一个坐标，到末尾后从头循环");
+                                let mut remove = None;
+                                for (index, point) in self.editing_config.click_points.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.label(format!("{}.", index + 1));
+                                        ui.label("X");
+                                        ui.add(egui::DragValue::new(&mut point.0));
+                                        ui.label("Y");
+                                        ui.add(egui::DragValue::new(&mut point.1));
+                                        
+                                        match self.pending_capture {
+                                            Some((CaptureTarget::SequencePoint(pending), deadline)) if pending == index => {
+                                                let remaining = deadline.saturating_duration_since(Instant::now());
+                                                ui.label(format!("{}秒后捕获...", remaining.as_secs() + 1));
+                                            }
+                                            _ => {
+                                                if ui.button("Capture").clicked() {
+                                                    self.pending_capture = Some((CaptureTarget::SequencePoint(index), Instant::now() + CAPTURE_DELAY));
+                                                }
+                                            }
+                                        }
+                                        
+                                        if ui.button("从剪贴板粘贴坐标").clicked() {
+                                            match paste_coordinate() {
+                                                Ok(position) => *point = position,
+                                                Err(err) => paste_error = Some(err),
+                                            }
+                                        }
+                                        if ui.button("删除").clicked() {
+                                            remove = Some(index);
+                                        }
+                                    });
+                                }
+                                if let Some(index) = remove {
+                                    self.editing_config.click_points.remove(index);
+                                    // 捕获倒计时指向的序号已经变化，取消它
+                                    if matches!(self.pending_capture, Some((CaptureTarget::SequencePoint(_), _))) {
+                                        self.pending_capture = None;
+                                    }
+                                }
+                                if ui.button("添加坐标").on_hover_text("在末尾添加鼠标当前位置，可以再捕获或修改").clicked() {
+                                    self.editing_config.click_points.push(Enigo::new().mouse_location());
+                                }
+                            }
+                            if let Some(err) = paste_error {
+                                self.set_status(err);
+                            }
+                            
+                            ui.add_space(5.0);
+                            
+                            // 根据点击模式显示不同的配置选项
+                            match self.editing_config.click_mode {
+                                ClickMode::FixedInterval => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Input Style:").on_hover_text("选择用毫秒间隔还是每秒点击次数来设置速率");
+                                        if ui.selectable_label(self.editing_config.rate_cps.is_none(), "间隔(ms)").clicked() {
+                                            if let Some(cps) = self.editing_config.rate_cps.take() {
+                                                self.editing_config.fixed_interval_ms = ((1000.0 / cps).round() as u64).max(1);
+                                            }
+                                        }
+                                        if ui.selectable_label(self.editing_config.rate_cps.is_some(), "频率(CPS)").clicked() && self.editing_config.rate_cps.is_none() {
+                                            self.editing_config.rate_cps = Some(1000.0 / self.editing_config.fixed_interval_ms.max(1) as f64);
+                                        }
+                                    });
+                                    
+                                    match &mut self.editing_config.rate_cps {
+                                        Some(cps) => {
+                                            ui.horizontal(|ui| {
+                                                ui.label("Click Rate (CPS):").on_hover_text("每秒点击次数");
+                                                ui.add(egui::DragValue::new(cps).speed(0.1).clamp_range(0.1..=1000.0));
+                                            });
+                                        }
+                                        None => {
+                                            ui.horizontal(|ui| {
+                                                ui.label("Click Interval (ms):").on_hover_text("两次点击之间的等待时间，单位毫秒，最长1小时");
+                                                ui.add(egui::DragValue::new(&mut self.editing_config.fixed_interval_ms).speed(10.0).clamp_range(1..=MAX_FIXED_INTERVAL_MS));
+                                                if self.editing_config.fixed_interval_ms >= 60_000 {
+                                                    ui.label(format_interval_ms(self.editing_config.fixed_interval_ms));
+                                                }
+                                            });
+                                        }
+                                    }
+                                },
+                                ClickMode::RandomInterval => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Min Interval (ms):").on_hover_text("随机间隔的下限，单位毫秒");
+                                        ui.add(egui::Slider::new(&mut self.editing_config.min_random_interval_ms, 1..=MAX_RANDOM_SLIDER_MS));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Max Interval (ms):").on_hover_text("随机间隔的上限，单位毫秒");
+                                        ui.add(egui::Slider::new(&mut self.editing_config.max_random_interval_ms, 1..=MAX_RANDOM_SLIDER_MS));
+                                    });
+                                },
+                                ClickMode::Continuous => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Target CPS:").on_hover_text("希望达到的每秒点击次数，实际速率会自动向它收敛");
+                                        ui.add(egui::Slider::new(&mut self.editing_config.target_cps, 1..=1000));
+                                    });
+                                },
+                                ClickMode::Pattern => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Click Interval Sequence (ms, comma separated):").on_hover_text("依次使用的间隔（毫秒），用逗号分隔，用完后从头循环");
+                                        ui.text_edit_singleline(&mut self.pattern_input);
+                                    });
+                                    let mut intervals = parse_pattern_intervals(&self.pattern_input);
+                                    if pattern_timeline(ui, &mut intervals) {
+                                        self.pattern_input = format_pattern_intervals(&intervals);
+                                        self.editing_config.pattern_intervals = intervals;
+                                    }
+                                    ui.checkbox(&mut self.editing_config.randomize_start_index, "Start at a random position")
+                                        .on_hover_text("每次开始时从序列中的随机位置开始，而不是第一个间隔");
+                                },
+                                ClickMode::Ramp => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Start Interval (ms):").on_hover_text("开始时的点击间隔，单位毫秒");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.ramp_start_ms).speed(10.0).clamp_range(1..=MAX_FIXED_INTERVAL_MS));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Target Interval (ms):").on_hover_text("加速结束后一直保持的点击间隔，单位毫秒");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.ramp_end_ms).speed(10.0).clamp_range(1..=MAX_FIXED_INTERVAL_MS));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Ramp Duration (ms):").on_hover_text("从开始间隔线性变到目标间隔所用的时间，单位毫秒");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.ramp_duration_ms).speed(100.0).clamp_range(0..=MAX_FIXED_INTERVAL_MS));
+                                    });
+                                },
+                                ClickMode::Burst => {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Burst Size:").on_hover_text("每组连续点击的次数");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.burst_size).clamp_range(1..=1000));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Burst Interval (ms):").on_hover_text("组内两次点击之间的间隔，单位毫秒");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.burst_interval_ms).clamp_range(1..=MAX_FIXED_INTERVAL_MS));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Pause (ms):").on_hover_text("每组点完后停顿的时间，单位毫秒");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.burst_pause_ms).speed(10.0).clamp_range(1..=MAX_FIXED_INTERVAL_MS));
+                                    });
+                                },
+                            }
+                            
+                            ui.horizontal(|ui| {
+                                let mut limited = self.editing_config.max_cps.is_some();
+                                if ui.checkbox(&mut limited, "Max CPS:").on_hover_text("本配置的速率上限，与设置菜单中的全局上限取较小值").changed() {
+                                    self.editing_config.max_cps = if limited { Some(self.settings.max_cps) } else { None };
+                                }
+                                if let Some(max_cps) = &mut self.editing_config.max_cps {
+                                    ui.add(egui::DragValue::new(max_cps).speed(1.0).clamp_range(0.1..=1000.0));
+                                }
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                let mut limited = self.editing_config.max_clicks.is_some();
+                                if ui.checkbox(&mut limited, "Stop after clicks:").on_hover_text("点击这么多次后自动停止，工具栏的\"点击N次\"会临时覆盖这个值").changed() {
+                                    self.editing_config.max_clicks = if limited { Some(self.quick_click_target) } else { None };
+                                }
+                                if let Some(max_clicks) = &mut self.editing_config.max_clicks {
+                                    ui.add(egui::DragValue::new(max_clicks).clamp_range(1..=1_000_000));
+                                }
+                            });
+                            
+                            ui.horizontal(|ui| {
+                                let mut limited = self.editing_config.max_duration_secs.is_some();
+                                if ui.checkbox(&mut limited, "Stop after:").on_hover_text("运行这么久后自动停止，和点击上限先到者生效").changed() {
+                                    self.editing_config.max_duration_secs = if limited { Some(60) } else { None };
+                                }
+                                if let Some(secs) = &mut self.editing_config.max_duration_secs {
+                                    let mut minutes = *secs / 60;
+                                    let mut seconds = *secs % 60;
+                                    let minutes_changed = ui.add(egui::DragValue::new(&mut minutes).clamp_range(0..=1440).suffix("min")).changed();
+                                    let seconds_changed = ui.add(egui::DragValue::new(&mut seconds).clamp_range(0..=59).suffix("s")).changed();
+                                    if minutes_changed || seconds_changed {
+                                        *secs = (minutes * 60 + seconds).max(1);
+                                    }
+                                }
+                            });
+                            
+                            if self.editing_config.max_clicks.is_some() || self.editing_config.max_duration_secs.is_some() {
                                 ui.horizontal(|ui| {
-                                    ui.label("Min Interval (ms):");
-                                    ui.add(egui::Slider::new(&mut self.editing_config.min_random_interval_ms, 1..=500));
+                                    ui.label("Repeat:").on_hover_text("达到点击或时长上限后整体重复几轮，1表示不重复");
+                                    ui.add(egui::DragValue::new(&mut self.editing_config.loop_count).clamp_range(1..=1000));
+                                    if self.editing_config.loop_count > 1 {
+                                        ui.label("Gap:").on_hover_text("两轮之间的停顿");
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.loop_gap_ms).clamp_range(0..=MAX_FIXED_INTERVAL_MS).suffix(" ms"));
+                                    }
                                 });
+                            }
+                            
+                            // 配置的速率超过上限时提示实际会被限速
+                            let max_cps = effective_max_cps(self.editing_config.max_cps, self.settings.max_cps);
+                            if self.editing_config.nominal_interval_ms() < 1000.0 / max_cps {
+                                ui.label(RichText::new(format!("⚠ 配置速率超过上限，实际不超过 {}", format_cps(max_cps))).color(Color32::YELLOW));
+                            }
+                            
+                            ui.add_space(5.0);
+                            
+                            let mut pixel_enabled = self.editing_config.pixel_condition.is_some();
+                            if ui.checkbox(&mut pixel_enabled, "Only click when a pixel matches")
+                                .on_hover_text("每次点击前读取屏幕像素，颜色与期望颜色相近时才点击")
+                                .changed()
+                            {
+                                self.editing_config.pixel_condition = if pixel_enabled { Some(PixelCondition::default()) } else { None };
+                            }
+                            if let Some(condition) = &mut self.editing_config.pixel_condition {
                                 ui.horizontal(|ui| {
-                                    ui.label("Max Interval (ms):");
-                                    ui.add(egui::Slider::new(&mut self.editing_config.max_random_interval_ms, 
-                                                            self.editing_config.min_random_interval_ms..=1000));
+                                    ui.label("Pixel:");
+                                    ui.label("X");
+                                    ui.add(egui::DragValue::new(&mut condition.x));
+                                    ui.label("Y");
+                                    ui.add(egui::DragValue::new(&mut condition.y));
+                                    ui.color_edit_button_srgb(&mut condition.color);
+                                    ui.label("Tolerance:").on_hover_text("每个颜色通道允许的最大偏差（0-255）");
+                                    ui.add(egui::DragValue::new(&mut condition.tolerance));
+                                    
+                                    match self.pending_capture {
+                                        Some((CaptureTarget::Pixel, deadline)) => {
+                                            let remaining = deadline.saturating_duration_since(Instant::now());
+                                            ui.label(format!("{}秒后取色...", remaining.as_secs() + 1));
+                                        }
+                                        _ => {
+                                            if ui.button("取色").on_hover_text("倒计时结束时读取鼠标位置和颜色").clicked() {
+                                                self.pending_capture = Some((CaptureTarget::Pixel, Instant::now() + CAPTURE_DELAY));
+                                            }
+                                        }
+                                    }
                                 });
-                            },
-                            ClickMode::Pattern => {
+                            }
+                            
+                            if self.editing_config.pixel_condition.is_some() || !self.editing_config.target_window_title.is_empty() {
                                 ui.horizontal(|ui| {
-                                    ui.label("Click Interval Sequence (ms, comma separated):");
-                                    ui.text_edit_singleline(&mut self.pattern_input);
+                                    let mut watchdog = self.editing_config.stuck_timeout_secs > 0;
+                                    if ui.checkbox(&mut watchdog, "Stop if the target looks frozen after")
+                                        .on_hover_text("点击持续进行但像素条件的位置（或目标窗口中心）的颜色一直不变时停止")
+                                        .changed()
+                                    {
+                                        self.editing_config.stuck_timeout_secs = if watchdog { 10 } else { 0 };
+                                    }
+                                    if watchdog {
+                                        ui.add(egui::DragValue::new(&mut self.editing_config.stuck_timeout_secs).clamp_range(1..=3600).suffix("s"));
+                                    }
                                 });
-                            },
-                            _ => {}
+                            }
+                            
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Relative Moves (dx,dy; dx,dy):").on_hover_text("每次点击前相对移动鼠标的像素数，用分号分隔多组，循环使用");
+                                ui.text_edit_singleline(&mut self.relative_moves_input);
+                            });
+                            
+                            ui.add_space(5.0);
+                            
+                            ui.horizontal(|ui| {
+                                ui.label("Target Window:").on_hover_text("目标窗口标题，留空表示不限定窗口");
+                                ui.text_edit_singleline(&mut self.editing_config.target_window_title);
+                            });
+                            ui.checkbox(&mut self.editing_config.stop_on_window_lost, "Stop when target window closes")
+                                .on_hover_text("找不到目标窗口时自动停止");
+                            ui.checkbox(&mut self.editing_config.restrict_to_window_client, "Skip clicks outside the target window")
+                                .on_hover_text("坐标不在目标窗口客户区内时跳过这次点击");
+                            ui.checkbox(&mut self.editing_config.confirm_before_start, "Confirm before start")
+                                .on_hover_text("开始前弹出确认框，防止误触发");
+                            if matches!(self.editing_config.action_type, ActionType::Drag { .. } | ActionType::FollowPath) {
+                                ui.checkbox(&mut self.editing_config.preposition_cursor, "Move the cursor to the start point on start")
+                                    .on_hover_text("开始时立即把鼠标移到拖动起点或路径起点，确认点击位置");
+                            }
+                        });
+                        
+                        // 启动前会做同样的检查，这里提前显示
+                        for issue in self.editing_config.validate() {
+                            let (prefix, color) = if issue.is_error() { ("✖", Color32::RED) } else { ("⚠", Color32::YELLOW) };
+                            ui.label(RichText::new(format!("{} {}", prefix, issue.message())).color(color));
                         }
                         
                         ui.add_space(10.0);
                         
                         ui.horizontal(|ui| {
-                            if ui.button("Save").clicked() {
-                                // 处理模式点击间隔
-                                if self.editing_config.click_mode == ClickMode::Pattern {
-                                    let mut intervals = Vec::new();
-                                    for part in self.pattern_input.split(',') {
-                                        if let Ok(interval) = part.trim().parse::<u64>() {
-                                            intervals.push(interval);
-                                        }
-                                    }
-                                    if !intervals.is_empty() {
-                                        self.editing_config.pattern_intervals = intervals;
-                                    }
-                                }
-                                
-                                // 保存配置
-                                if self.selected_config_index < self.configs.len() {
-                                    self.configs[self.selected_config_index] = self.editing_config.clone();
-                                } else {
-                                    self.configs.push(self.editing_config.clone());
-                                    self.selected_config_index = self.configs.len() - 1;
-                                }
-                                
-                                // 应用配置
-                                self.apply_config(self.editing_config.clone());
-                                
-                                // 保存到文件
-                                let _ = self.save_configs();
-                                
-                                self.is_editing = false;
+                            if ui.add_enabled(!frozen, egui::Button::new("应用")).on_hover_text("只应用到当前运行状态，不写入文件").clicked() {
+                                self.apply_editing_config();
+                            }
+                            
+                            if ui.add_enabled(!frozen, egui::Button::new("保存")).clicked() {
+                                self.save_editing_config();
                             }
                             
-                            if ui.button("Cancel").clicked() {
-                                self.is_editing = false;
+                            if ui.button("取消").clicked() {
+                                self.cancel_editing();
+                            }
+                            
+                            if ui.add_enabled(!frozen, egui::Button::new("重置为默认")).clicked() {
+                                self.reset_editing_config();
+                            }
+                            
+                            if self.trial_applied {
+                                ui.label(RichText::new("● 已应用，未保存").color(Color32::YELLOW));
                             }
                         });
                     });
             }
         });
 
-        // Request a repaint to ensure the UI is continuously updated
-        ctx.request_repaint();
+        // Delete键删除选中配置：任何对话框（包括热键绑定）打开或文本框有焦点时不响应
+        if self.settings.delete_key_deletes_config
+            && !self.modal_open()
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.key_pressed(egui::Key::Delete))
+        {
+            self.pending_delete = true;
+        }
+        
+        // 运行中滚轮实时调速：向上加快，向下减慢，Ctrl细调
+        // 放在面板之后，滚动区域已消耗的滚动量不会再被算作调速
+        if self.stats.is_running && !self.modal_open() && ctx.input(|i| i.pointer.hover_pos().is_some()) {
+            // 按住Ctrl时egui把滚轮转换为缩放量
+            let (scroll, zoom) = ctx.input(|i| (i.scroll_delta.y, i.zoom_delta()));
+            if scroll != 0.0 {
+                self.adjust_live_rate(scroll > 0.0, false);
+            } else if zoom != 1.0 {
+                self.adjust_live_rate(zoom > 1.0, true);
+            }
+        }
+        
+        // 删除确认对话框
+        if self.pending_delete {
+            let config_name = self.configs.get(self.selected_config_index).map(|config| config.name.clone()).unwrap_or_default();
+            egui::Window::new("确认删除")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("确定要删除配置「{}」吗？", config_name));
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("删除").clicked() {
+                            self.pending_delete = false;
+                            self.delete_selected_config();
+                        }
+                        if ui.button("取消").clicked() {
+                            self.pending_delete = false;
+                        }
+                    });
+                });
+        }
+        
+        // 批量删除确认对话框
+        if self.pending_bulk_delete {
+            let count = self.selected_rows.len();
+            egui::Window::new("确认批量删除")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("确定要删除选中的 {} 个配置吗？", count));
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("删除").clicked() {
+                            self.pending_bulk_delete = false;
+                            self.delete_selected_rows();
+                        }
+                        if ui.button("取消").clicked() {
+                            self.pending_bulk_delete = false;
+                        }
+                    });
+                });
+        }
+        
+        // 开始前确认对话框
+        if self.pending_start {
+            let config_name = self.configs.get(self.selected_config_index).map(|config| config.name.clone()).unwrap_or_default();
+            egui::Window::new("确认开始")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("确定要开始运行配置「{}」吗？", config_name));
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("开始").clicked() {
+                            self.pending_start = false;
+                            self.start_clicker();
+                        }
+                        if ui.button("取消").clicked() {
+                            self.pending_start = false;
+                        }
+                    });
+                });
+        }
+        
+        // 使用统计窗口，只读取本地记录
+        if self.show_usage_dashboard {
+            let mut open = true;
+            egui::Window::new("使用统计")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    if !self.settings.record_usage_history {
+                        ui.label(RichText::new("未开启\"记录使用历史\"，新的运行不会被统计").color(Color32::YELLOW));
+                    }
+                    
+                    let summary = summarize_usage(&self.usage_history);
+                    egui::Grid::new("usage_summary").num_columns(2).show(ui, |ui| {
+                        ui.label("运行次数:");
+                        ui.label(summary.sessions.to_string());
+                        ui.end_row();
+                        ui.label("总点击数:");
+                        ui.label(summary.total_clicks.to_string());
+                        ui.end_row();
+                        ui.label("平均时长:");
+                        ui.label(format_duration_secs(summary.average_duration_secs));
+                        ui.end_row();
+                        ui.label("最常用配置:");
+                        ui.label(match &summary.most_used_config {
+                            Some((name, count)) => format!("{} ({} 次)", name, count),
+                            None => "无".to_string(),
+                        });
+                        ui.end_row();
+                    });
+                    
+                    ui.add_space(5.0);
+                    ui.label("按星期统计运行次数 (UTC):");
+                    draw_weekday_chart(ui, &summary.sessions_by_weekday);
+                    
+                    if !self.usage_history.is_empty() && ui.button("清除历史").clicked() {
+                        self.usage_history.clear();
+                        if let Err(err) = save_usage_history(&self.usage_history) {
+                            self.set_status(format!("清除使用历史失败: {}", err));
+                        }
+                    }
+                });
+            self.show_usage_dashboard = open;
+        }
+        
+        // 诊断窗口
+        if let Some(mut report) = self.diagnostics.clone() {
+            let mut open = true;
+            let mut refresh = false;
+            egui::Window::new("诊断")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.add(egui::TextEdit::multiline(&mut report)
+                        .font(egui::TextStyle::Monospace)
+                        .desired_width(480.0)
+                        .interactive(false));
+                    
+                    ui.horizontal(|ui| {
+                        if ui.button("复制").clicked() {
+                            ctx.output_mut(|output| output.copied_text = report.clone());
+                            self.set_status("诊断信息已复制到剪贴板");
+                        }
+                        if ui.button("运行输入自检").on_hover_text("把鼠标移动1像素再移回，确认输入注入是否生效").clicked() {
+                            self.self_test();
+                            refresh = true;
+                        }
+                        refresh |= ui.button("刷新").clicked();
+                    });
+                });
+            self.diagnostics = match (open, refresh) {
+                (false, _) => None,
+                (true, true) => Some(self.diagnostics_report()),
+                (true, false) => Some(report),
+            };
+        }
+        
+        // 热键绑定对话框：下一个按下的受支持按键成为新的热键
+        if self.capturing_hotkey {
+            let pressed = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key { key, pressed: true, modifiers, .. } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+            let mut cancelled = false;
+            match pressed {
+                Some((egui::Key::Escape, _)) => cancelled = true,
+                Some((key, modifiers)) => match hotkey_from_egui(key, modifiers) {
+                    Some((modifiers, keycode)) => {
+                        self.capturing_hotkey = false;
+                        self.rebind_hotkey(modifiers, keycode);
+                    }
+                    None => self.set_status("不支持这个按键，请使用F1-F12、字母、数字或导航键"),
+                },
+                None => {}
+            }
+            
+            egui::Window::new("设置热键")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("当前热键: {}", self.hotkey_name()));
+                    ui.label("请按下新的热键，可以组合 Ctrl / Alt / Shift");
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("恢复F8").clicked() {
+                            self.capturing_hotkey = false;
+                            self.rebind_hotkey(DEFAULT_HOTKEY_MODIFIERS, DEFAULT_HOTKEY_KEYCODE);
+                        }
+                        cancelled |= ui.button("取消 (Esc)").clicked();
+                    });
+                });
+            if cancelled {
+                self.capturing_hotkey = false;
+            }
+        }
+        
+        // 便携模式首次运行的导入询问
+        if self.pending_portable_import {
+            egui::Window::new("便携模式")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("在 {} 中找到了已有的配置，要导入到便携目录吗？", standard_config_dir().display()));
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("导入").clicked() {
+                            self.pending_portable_import = false;
+                            match self.import_standard_configs() {
+                                Ok(()) => self.set_status("已导入系统配置目录中的配置和设置，部分设置重启后生效"),
+                                Err(err) => self.set_status(format!("导入失败: {}", err)),
+                            }
+                        }
+                        if ui.button("不导入").clicked() {
+                            self.pending_portable_import = false;
+                            // 写出当前配置，下次启动不再询问
+                            if let Err(err) = self.save_configs() {
+                                self.set_status(format!("保存配置失败: {}", err));
+                            }
+                        }
+                    });
+                });
+        }
+        
+        // 记住选中的配置，选中位置可能在上面任何地方改变
+        if self.selected_config_index != self.settings.last_config_index {
+            self.settings.last_config_index = self.selected_config_index;
+            self.save_settings();
+        }
+        
+        // 高亮渐隐期间逐帧重绘
+        if flash > 0.0 {
+            ctx.request_repaint();
+        }
+        
+        // 运行中定时刷新运行时间，捕获坐标时刷新倒计时
+        if self.stats.is_running || self.pending_capture.is_some() || self.path_recording.is_some() {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        } else if self.awaiting_unlock {
+            ctx.request_repaint_after(UNLOCK_POLL_INTERVAL);
+        } else if self.settings.reload_configs_on_change {
+            // 空闲时也要定期醒来检查配置文件
+            ctx.request_repaint_after(CONFIG_WATCH_INTERVAL);
+        }
     }
 }
 
+// CJK Font
+// egui默认字体不含中文；优先使用随程序分发的字体（程序目录或当前目录下的assets/fonts），其次是各平台常见的系统中文字体
+const CJK_FONT_NAME: &str = "cjk";
+const BUNDLED_FONT_PATH: &str = "assets/fonts/NotoSansSC-Regular.otf";
+const SYSTEM_CJK_FONTS: [&str; 9] = [
+    r"C:\Windows\Fonts\msyh.ttc",
+    r"C:\Windows\Fonts\simhei.ttf",
+    "/System/Library/Fonts/PingFang.ttc",
+    "/System/Library/Fonts/STHeiti Light.ttc",
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+    "/usr/share/fonts/wenquanyi/wqy-microhei/wqy-microhei.ttc",
+];
+
+fn load_cjk_font() -> Option<Vec<u8>> {
+    let exe_dir = std::env::current_exe().ok().and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()));
+    let bundled = exe_dir
+        .into_iter()
+        .chain(std::iter::once(PathBuf::from(".")))
+        .map(|dir| dir.join(BUNDLED_FONT_PATH));
+    
+    bundled
+        .chain(SYSTEM_CJK_FONTS.iter().map(PathBuf::from))
+        .find_map(|path| fs::read(path).ok().filter(|bytes| is_font_file(bytes)))
+}
+
+// egui在set_fonts时遇到无法解析的字体会panic，先检查文件头
+fn is_font_file(bytes: &[u8]) -> bool {
+    matches!(bytes.get(..4), Some(b"\x00\x01\x00\x00" | b"OTTO" | b"true" | b"ttcf"))
+}
+
 fn main() -> Result<(), eframe::Error> {
+    // 基准测试不需要显示器，在初始化eframe之前返回
+    if std::env::args().any(|arg| arg == BENCHMARK_FLAG) {
+        run_benchmark();
+        return Ok(());
+    }
+    
+    // 无显示器的CI环境：给出明确提示，而不是在创建窗口或注入输入时崩溃
+    if !display_available() {
+        eprintln!("No display found (DISPLAY and WAYLAND_DISPLAY are not set); SeriousClick needs a desktop session.");
+        eprintln!("Use {} to run the click loop against a mock input backend without a display.", BENCHMARK_FLAG);
+        std::process::exit(1);
+    }
+    
+    let start_minimized = std::env::args().any(|arg| arg == MINIMIZED_FLAG);
+    
+    // 两个实例同时点击会互相干扰，热键也只能有一个注册成功
+    let mut instance_port_in_use = false;
+    let _instance_lock = if AppSettings::load().allow_multiple_instances {
+        None
+    } else {
+        match acquire_instance_lock() {
+            InstanceLock::Acquired(lock) => Some(lock),
+            // 端口被无关的程序占用时照常启动，只是无法阻止多开
+            InstanceLock::PortInUse => {
+                instance_port_in_use = true;
+                None
+            }
+            InstanceLock::AlreadyRunning => {
+                rfd::MessageDialog::new()
+                    .set_level(rfd::MessageLevel::Warning)
+                    .set_title("SeriousClick")
+                    .set_description("SeriousClick 已经在运行。\n如需同时运行多个实例，请在已运行实例的\"设置\"菜单中开启\"允许多开\"。")
+                    .set_buttons(rfd::MessageButtons::Ok)
+                    .show();
+                return Ok(());
+            }
+        }
+    };
+    
     let options = eframe::NativeOptions {
         initial_window_size: Some(Vec2::new(800.0, 600.0)),
         resizable: true,
@@ -710,26 +5953,26 @@ fn main() -> Result<(), eframe::Error> {
     let result = eframe::run_native(
         "SeriousClick Auto Clicker",
         options,
-        Box::new(|cc| {
+        Box::new(move |cc| {
             // 设置UI比例
             cc.egui_ctx.set_pixels_per_point(1.25); // 增加UI比例，使文字更清晰
             
             // 使用默认字体配置
             let mut fonts = egui::FontDefinitions::default();
             
-            // 设置中文字体范围
-            let mut font_data = egui::FontData::from_static(include_bytes!("C:\\Windows\\Fonts\\simhei.ttf"));
-            // 设置字体支持的字符范围
-            font_data.tweak.scale = 1.0;
-            
-            // 添加中文字体
-            fonts.font_data.insert("simhei".to_owned(), font_data);
-            
-            // 将中文字体设置为首选字体
-            fonts.families.get_mut(&egui::FontFamily::Proportional).unwrap().insert(0, "simhei".to_owned());
-            fonts.families.get_mut(&egui::FontFamily::Monospace).unwrap().insert(0, "simhei".to_owned());
-            
-            // 不添加不存在的备用字体，使用默认字体
+            // 找到中文字体时设为首选字体，找不到时保留egui默认字体（中文会显示为方块）
+            let cjk_font = load_cjk_font();
+            let cjk_font_missing = cjk_font.is_none();
+            if let Some(bytes) = cjk_font {
+                let mut font_data = egui::FontData::from_owned(bytes);
+                font_data.tweak.scale = 1.0;
+                fonts.font_data.insert(CJK_FONT_NAME.to_owned(), font_data);
+                for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+                    if let Some(names) = fonts.families.get_mut(&family) {
+                        names.insert(0, CJK_FONT_NAME.to_owned());
+                    }
+                }
+            }
             
             cc.egui_ctx.set_fonts(fonts);
             
@@ -744,7 +5987,15 @@ fn main() -> Result<(), eframe::Error> {
             ].into();
             cc.egui_ctx.set_style(style);
             
-            Box::new(SeriousClickerApp::new())
+            let mut app = SeriousClickerApp::new(cc.egui_ctx.clone(), start_minimized);
+            // 没有中文字体时中文无法显示，这条提示用英文
+            if cjk_font_missing {
+                app.set_status(format!("No CJK font found, put {} next to the program", BUNDLED_FONT_PATH));
+            }
+            if instance_port_in_use {
+                app.set_status(format!("端口 {} 被其他程序占用，本次无法检查是否重复启动", INSTANCE_LOCK_PORT));
+            }
+            Box::new(app)
         })
     );
     
@@ -753,3 +6004,217 @@ fn main() -> Result<(), eframe::Error> {
     
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    // 每个测试使用独立的临时目录
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("seriousclick-test-{}-{}", std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+    
+    #[test]
+    fn writable_config_dir_is_used_directly() {
+        let base = test_dir("writable");
+        let preferred = base.join("config");
+        
+        let (dir, fallback_from) = choose_config_dir(preferred.clone(), &[base.join("fallback")]);
+        assert_eq!(dir, preferred);
+        assert_eq!(fallback_from, None);
+        
+        let _ = fs::remove_dir_all(&base);
+    }
+    
+    #[test]
+    fn read_only_config_dir_falls_back() {
+        // 父路径是普通文件时目录无法创建，以root运行时权限位也拦不住写入，所以用这种方式模拟只读
+        let base = test_dir("read-only");
+        let blocker = base.join("blocker");
+        fs::write(&blocker, b"").unwrap();
+        let preferred = blocker.join("config");
+        let fallback = base.join("fallback");
+        
+        let (dir, fallback_from) = choose_config_dir(preferred.clone(), &[blocker.join("also-blocked"), fallback.clone()]);
+        assert_eq!(dir, fallback);
+        assert_eq!(fallback_from, Some(preferred));
+        assert!(dir_is_writable(&dir));
+        
+        let _ = fs::remove_dir_all(&base);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn read_only_permissions_fall_back() {
+        use std::os::unix::fs::PermissionsExt;
+        
+        let base = test_dir("read-only-perms");
+        let locked = base.join("locked");
+        fs::create_dir_all(&locked).unwrap();
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o555)).unwrap();
+        let fallback = base.join("fallback");
+        
+        // root不受权限位限制，这时无法模拟只读
+        if !dir_is_writable(&locked) {
+            let (dir, fallback_from) = choose_config_dir(locked.clone(), &[fallback.clone()]);
+            assert_eq!(dir, fallback);
+            assert_eq!(fallback_from, Some(locked.clone()));
+        }
+        
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = fs::remove_dir_all(&base);
+    }
+    
+    #[test]
+    fn inverted_random_bounds_do_not_panic() {
+        let config = ClickerConfig {
+            click_mode: ClickMode::RandomInterval,
+            min_random_interval_ms: 20,
+            max_random_interval_ms: 5,
+            ..ClickerConfig::default()
+        };
+        let mut state = ClickerState::from(&config);
+        assert!(state.min_random_interval_ms <= state.max_random_interval_ms);
+        
+        // 绕过From直接把状态改乱，点击循环自身也要能处理
+        state.min_random_interval_ms = 20;
+        state.max_random_interval_ms = 5;
+        state.click_limit = Some(3);
+        state.is_running = true;
+        state.start_time = Some(Instant::now());
+        let state = Arc::new(Mutex::new(state));
+        
+        let mut backend = MockInputBackend::default();
+        run_click_loop(&mut backend, &state, |_| {});
+        assert_eq!(backend.clicks.len(), 3);
+    }
+    
+    #[test]
+    fn jitter_offset_stays_within_radius() {
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for radius in [0, 1, 3, 10, 50] {
+            for _ in 0..10_000 {
+                let (dx, dy) = jitter_offset(&mut rng, radius);
+                let radius = radius as i64;
+                assert!((dx as i64).pow(2) + (dy as i64).pow(2) <= radius * radius);
+            }
+        }
+    }
+    
+    #[test]
+    fn loop_count_repeats_the_click_limit() {
+        let config = ClickerConfig {
+            fixed_interval_ms: 1,
+            loop_count: 3,
+            loop_gap_ms: 0,
+            ..ClickerConfig::default()
+        };
+        let mut state = ClickerState::from(&config);
+        state.click_limit = Some(2);
+        state.is_running = true;
+        state.start_time = Some(Instant::now());
+        let state = Arc::new(Mutex::new(state));
+        
+        let mut backend = MockInputBackend::default();
+        run_click_loop(&mut backend, &state, |_| {});
+        assert_eq!(backend.clicks.len(), 6);
+        assert_eq!(lock_state(&state).loops_done, 2);
+    }
+    
+    #[test]
+    fn windows_virtual_keys_cover_f13_to_f24_and_numpad() {
+        assert_eq!(windows_virtual_key("F13"), Some(0x7C));
+        assert_eq!(windows_virtual_key("F24"), Some(0x87));
+        assert_eq!(windows_virtual_key("F12"), None);
+        assert_eq!(windows_virtual_key("F25"), None);
+        assert_eq!(windows_virtual_key("Numpad0"), Some(0x60));
+        assert_eq!(windows_virtual_key("Numpad9"), Some(0x69));
+        assert_eq!(windows_virtual_key("Numpad10"), None);
+        for (_, names) in PRESS_KEY_GROUPS {
+            for name in names {
+                assert!(enigo_key(name).is_some() || windows_virtual_key(name).is_some(), "{}", name);
+            }
+        }
+    }
+    
+    // 用固定种子跑几次固定坐标点击，随机偏移使每次移动的坐标都取决于随机序列
+    fn seeded_run(mode: ClickMode, seed: u64) -> MockInputBackend {
+        let config = ClickerConfig {
+            click_mode: mode,
+            min_random_interval_ms: 1,
+            max_random_interval_ms: 10,
+            pattern_intervals: vec![10, 80, 150],
+            randomize_start_index: true,
+            target_position: Some((100, 100)),
+            position_jitter_px: 20,
+            ..ClickerConfig::default()
+        };
+        let mut state = ClickerState::from(&config);
+        state.rng_seed = Some(seed);
+        state.click_limit = Some(6);
+        state.is_running = true;
+        state.start_time = Some(Instant::now());
+        let state = Arc::new(Mutex::new(state));
+        
+        let mut backend = MockInputBackend::default();
+        run_click_loop(&mut backend, &state, |_| {});
+        backend
+    }
+    
+    // 把实际点击间隔归到最接近的模式间隔，得到每次使用的模式索引
+    fn pattern_indices(backend: &MockInputBackend) -> Vec<usize> {
+        let pattern = [10.0, 80.0, 150.0];
+        backend.clicks
+            .windows(2)
+            .map(|pair| {
+                let interval_ms = (pair[1] - pair[0]).as_secs_f64() * 1000.0;
+                (0..pattern.len())
+                    .min_by(|&a, &b| (pattern[a] - interval_ms).abs().total_cmp(&(pattern[b] - interval_ms).abs()))
+                    .unwrap()
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn fixed_seed_repeats_random_interval_and_jitter() {
+        let first = seeded_run(ClickMode::RandomInterval, 42);
+        let second = seeded_run(ClickMode::RandomInterval, 42);
+        assert_eq!(first.clicks.len(), 6);
+        assert_eq!(first.moves, second.moves);
+        
+        let other = seeded_run(ClickMode::RandomInterval, 43);
+        assert_ne!(first.moves, other.moves);
+    }
+    
+    #[test]
+    fn fixed_seed_repeats_pattern_start_and_jitter() {
+        let first = seeded_run(ClickMode::Pattern, 7);
+        let second = seeded_run(ClickMode::Pattern, 7);
+        assert_eq!(first.moves, second.moves);
+        assert_eq!(pattern_indices(&first), pattern_indices(&second));
+        
+        let other = seeded_run(ClickMode::Pattern, 8);
+        assert_ne!(first.moves, other.moves);
+    }
+    
+    #[test]
+    fn huge_foreign_intervals_are_clamped() {
+        let lines = ["Hours=18446744073709551615", "Minutes=5", "MouseButton=Right"];
+        let configs = parse_key_value_configs(&lines, "imported");
+        assert_eq!(configs.len(), 1);
+        assert_eq!(configs[0].fixed_interval_ms, MAX_FIXED_INTERVAL_MS);
+        assert_eq!(configs[0].mouse_button, MouseButtonType::Right);
+    }
+    
+    #[test]
+    fn logical_to_physical_scales_and_rounds() {
+        let pos = egui::pos2(100.0, 200.0);
+        assert_eq!(logical_to_physical(pos, 1.0), (100, 200));
+        assert_eq!(logical_to_physical(pos, 1.25), (125, 250));
+        assert_eq!(logical_to_physical(pos, 2.0), (200, 400));
+        assert_eq!(logical_to_physical(egui::pos2(10.4, 3.0), 1.25), (13, 4));
+    }
+}